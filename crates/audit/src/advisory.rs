@@ -0,0 +1,47 @@
+use clap::ValueEnum;
+use espresso_common::{PackageName, Version, VersionReq};
+use schematic::{derive_enum, ConfigEnum};
+use serde::{Deserialize, Serialize};
+
+derive_enum!(
+    #[derive(ConfigEnum, Copy, Default, Ord, PartialOrd, ValueEnum)]
+    pub enum AdvisorySeverity {
+        #[default]
+        Low,
+        Medium,
+        High,
+        Critical,
+    }
+);
+
+/// A known vulnerability for a range of package versions, as defined
+/// by a TOML file in the advisory database.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Advisory {
+    /// Unique identifier, defaults to the file name.
+    #[serde(default)]
+    pub id: String,
+
+    pub package: PackageName,
+
+    pub affected: VersionReq,
+
+    pub severity: AdvisorySeverity,
+
+    pub description: String,
+}
+
+impl Advisory {
+    pub fn affects(&self, package: &PackageName, version: &Version) -> bool {
+        &self.package == package && self.affected.matches(version)
+    }
+}
+
+/// A locked package that is affected by an advisory.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct AdvisoryFinding {
+    pub advisory: Advisory,
+    pub package: PackageName,
+    pub version: Version,
+}
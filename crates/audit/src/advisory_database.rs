@@ -0,0 +1,133 @@
+use crate::advisory::{Advisory, AdvisoryFinding};
+use crate::audit_error::AuditError;
+use espresso_lockfile::Lockfile;
+use miette::IntoDiagnostic;
+use starbase_styles::color;
+use starbase_utils::{fs, glob, toml};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::debug;
+
+/// A local directory of advisory TOML files, optionally backed by a Git repository.
+#[derive(Debug)]
+pub struct AdvisoryDatabase {
+    pub advisories: Vec<Advisory>,
+    pub root: PathBuf,
+}
+
+impl AdvisoryDatabase {
+    pub fn load_from<P: AsRef<Path>>(root: P) -> miette::Result<AdvisoryDatabase> {
+        let root = root.as_ref();
+
+        debug!(database = ?root, "Loading advisory database");
+
+        if !root.exists() {
+            return Err(AuditError::MissingDatabase {
+                path: root.to_path_buf(),
+            }
+            .into());
+        }
+
+        let mut advisories = vec![];
+
+        for file in glob::walk_files(root, ["**/*.toml"])? {
+            let mut advisory: Advisory = toml::read_file(&file)?;
+
+            if advisory.id.is_empty() {
+                advisory.id = fs::file_name(&file).trim_end_matches(".toml").to_owned();
+            }
+
+            advisories.push(advisory);
+        }
+
+        debug!(
+            database = ?root,
+            "Loaded {} advisories",
+            advisories.len()
+        );
+
+        Ok(AdvisoryDatabase {
+            advisories,
+            root: root.to_path_buf(),
+        })
+    }
+
+    /// Clone the database from the provided repository, or pull the latest
+    /// changes if the database has already been cloned.
+    pub async fn update<P: AsRef<Path>>(root: P, repository: Option<&str>) -> miette::Result<()> {
+        let root = root.as_ref();
+        let mut command = Command::new("git");
+
+        if root.join(".git").exists() {
+            debug!(database = ?root, "Pulling latest advisories");
+
+            command
+                .args(["pull", "--ff-only", "--quiet"])
+                .current_dir(root);
+        } else if let Some(repository) = repository {
+            debug!(database = ?root, repository, "Cloning advisory database");
+
+            if let Some(parent) = root.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            command
+                .args(["clone", "--depth", "1", "--quiet", repository])
+                .arg(root);
+        } else {
+            return Err(AuditError::MissingRepository {
+                path: root.to_path_buf(),
+            }
+            .into());
+        }
+
+        let status = command
+            .spawn()
+            .into_diagnostic()?
+            .wait()
+            .await
+            .into_diagnostic()?;
+
+        if !status.success() {
+            let std_command = command.as_std();
+
+            return Err(AuditError::GitFailed {
+                command: format!(
+                    "{} {}",
+                    std_command.get_program().to_string_lossy(),
+                    std_command
+                        .get_args()
+                        .map(|arg| arg.to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                ),
+                path: root.to_path_buf(),
+            }
+            .into());
+        }
+
+        debug!(database = ?root, "Updated advisory database with {}", color::shell("git"));
+
+        Ok(())
+    }
+
+    /// Return all locked packages that are affected by a known advisory.
+    pub fn audit(&self, lockfile: &Lockfile) -> Vec<AdvisoryFinding> {
+        let mut findings = vec![];
+
+        for package in &lockfile.packages {
+            for advisory in &self.advisories {
+                if advisory.affects(&package.name, &package.version) {
+                    findings.push(AdvisoryFinding {
+                        advisory: advisory.to_owned(),
+                        package: package.name.clone(),
+                        version: package.version.clone(),
+                    });
+                }
+            }
+        }
+
+        findings.sort_by(|a, b| b.advisory.severity.cmp(&a.advisory.severity));
+        findings
+    }
+}
@@ -0,0 +1,42 @@
+use crate::advisory::AdvisorySeverity;
+use miette::Diagnostic;
+use starbase_styles::{Style, Stylize};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+pub enum AuditError {
+    #[diagnostic(code(audit::database::git_failed))]
+    #[error(
+        "Failed to update the advisory database at {}. {} exited with a non-zero status.",
+        .path.style(Style::Path),
+        .command.style(Style::Shell),
+    )]
+    GitFailed { command: String, path: PathBuf },
+
+    #[diagnostic(code(audit::database::missing))]
+    #[error(
+        "No advisory database found at {}. Pass {} to download one.",
+        .path.style(Style::Path),
+        "--update".style(Style::Label),
+    )]
+    MissingDatabase { path: PathBuf },
+
+    #[diagnostic(code(audit::database::missing_repository))]
+    #[error(
+        "Unable to download the advisory database to {}, as no repository was provided. Pass {} with a Git URL.",
+        .path.style(Style::Path),
+        "--repo".style(Style::Label),
+    )]
+    MissingRepository { path: PathBuf },
+
+    #[diagnostic(code(audit::vulnerabilities_found))]
+    #[error(
+        "Found {count} vulnerable package(s) with a severity of {} or higher.",
+        .severity.to_string().style(Style::Symbol),
+    )]
+    VulnerabilitiesFound {
+        count: usize,
+        severity: AdvisorySeverity,
+    },
+}
@@ -0,0 +1,7 @@
+mod advisory;
+mod advisory_database;
+mod audit_error;
+
+pub use advisory::*;
+pub use advisory_database::*;
+pub use audit_error::*;
@@ -0,0 +1,122 @@
+use espresso_audit::*;
+use espresso_lockfile::{Lockfile, LOCKFILE_NAME};
+use starbase_sandbox::create_empty_sandbox;
+
+fn create_advisories(sandbox: &starbase_sandbox::Sandbox) {
+    sandbox.create_file(
+        "advisories/ns/pkg-a/ESPA-0001.toml",
+        r#"
+package = "ns/pkg-a"
+affected = "<1.2.0"
+severity = "high"
+description = "Prototype pollution."
+"#,
+    );
+
+    sandbox.create_file(
+        "advisories/ns/pkg-b/ESPA-0002.toml",
+        r#"
+id = "ESPA-0002"
+package = "ns/pkg-b"
+affected = ">=2.0.0, <2.1.0"
+severity = "low"
+description = "Regular expression denial of service."
+"#,
+    );
+}
+
+mod advisory_database {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "No advisory database found")]
+    fn errors_if_missing() {
+        let sandbox = create_empty_sandbox();
+
+        AdvisoryDatabase::load_from(sandbox.path().join("advisories")).unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "git clone --depth 1 --quiet")]
+    async fn includes_git_arguments_on_failure() {
+        let sandbox = create_empty_sandbox();
+
+        AdvisoryDatabase::update(
+            sandbox.path().join("advisories"),
+            Some(sandbox.path().join("missing-repo").to_str().unwrap()),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[test]
+    fn loads_advisories() {
+        let sandbox = create_empty_sandbox();
+        create_advisories(&sandbox);
+
+        let db = AdvisoryDatabase::load_from(sandbox.path().join("advisories")).unwrap();
+
+        assert_eq!(db.advisories.len(), 2);
+        assert!(db.advisories.iter().any(|a| a.id == "ESPA-0001"));
+        assert!(db.advisories.iter().any(|a| a.id == "ESPA-0002"));
+    }
+
+    #[test]
+    fn finds_affected_packages() {
+        let sandbox = create_empty_sandbox();
+        create_advisories(&sandbox);
+        sandbox.create_file(
+            LOCKFILE_NAME,
+            r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.1.9"
+
+[[package]]
+name = "ns/pkg-b"
+version = "2.1.0"
+
+[[package]]
+name = "ns/pkg-c"
+version = "1.0.0"
+"#,
+        );
+
+        let db = AdvisoryDatabase::load_from(sandbox.path().join("advisories")).unwrap();
+        let findings = db.audit(&Lockfile::load(sandbox.path()).unwrap());
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].advisory.id, "ESPA-0001");
+        assert_eq!(findings[0].advisory.severity, AdvisorySeverity::High);
+        assert_eq!(findings[0].version.to_string(), "1.1.9");
+    }
+
+    #[test]
+    fn sorts_by_severity() {
+        let sandbox = create_empty_sandbox();
+        create_advisories(&sandbox);
+        sandbox.create_file(
+            LOCKFILE_NAME,
+            r#"
+[[package]]
+name = "ns/pkg-b"
+version = "2.0.5"
+
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+"#,
+        );
+
+        let db = AdvisoryDatabase::load_from(sandbox.path().join("advisories")).unwrap();
+        let findings = db.audit(&Lockfile::load(sandbox.path()).unwrap());
+
+        assert_eq!(
+            findings
+                .iter()
+                .map(|f| f.advisory.severity)
+                .collect::<Vec<_>>(),
+            vec![AdvisorySeverity::High, AdvisorySeverity::Low]
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use crate::commands::{BuildArgs, NewArgs};
+use crate::commands::{
+    AuditArgs, BuildArgs, CheckArgs, LicensesArgs, NewArgs, SbomArgs, SyncArgs, VendorArgs,
+};
 use clap::{Parser, Subcommand};
 use espresso_common::PackageName;
 use espresso_workspace::SelectQuery;
@@ -39,6 +41,14 @@ pub enum Commands {
     Init(NewArgs),
 
     // PACKAGE MANAGEMENT
+    #[command(
+        name = "audit",
+        about = "Audit dependencies for known vulnerabilities.",
+        long_about = "Audit locked dependencies against a local security advisory database, and fail if a vulnerability at or above the chosen severity is found.",
+        next_help_heading = HEADING_PKG_MANAGEMENT,
+    )]
+    Audit(AuditArgs),
+
     #[command(
         alias = "b",
         name = "build",
@@ -48,6 +58,22 @@ pub enum Commands {
     )]
     Build(BuildArgs),
 
+    #[command(
+        name = "check",
+        about = "Type check a package.",
+        long_about = "Type check a package's `src` and `tests` directories with TypeScript, without emitting any files.",
+        next_help_heading = HEADING_PKG_MANAGEMENT,
+    )]
+    Check(CheckArgs),
+
+    #[command(
+        name = "licenses",
+        about = "List the licenses of all dependencies.",
+        long_about = "List the licenses of workspace packages and locked dependencies, and fail if a dependency's license is not allowed by the `[licenses]` policy.",
+        next_help_heading = HEADING_PKG_MANAGEMENT,
+    )]
+    Licenses(LicensesArgs),
+
     #[command(
         alias = "n",
         name = "new",
@@ -55,6 +81,30 @@ pub enum Commands {
         next_help_heading = HEADING_PKG_MANAGEMENT,
     )]
     New(NewArgs),
+
+    #[command(
+        name = "sbom",
+        about = "Generate a software bill of materials.",
+        long_about = "Generate a software bill of materials (SBOM) for the selected packages and their locked dependencies, in the CycloneDX or SPDX JSON format.",
+        next_help_heading = HEADING_PKG_MANAGEMENT,
+    )]
+    Sbom(SbomArgs),
+
+    #[command(
+        name = "sync",
+        about = "Sync generated files.",
+        long_about = "Sync files that are generated from the workspace and package manifests, like `tsconfig.json` files for editors.",
+        next_help_heading = HEADING_PKG_MANAGEMENT,
+    )]
+    Sync(SyncArgs),
+
+    #[command(
+        name = "vendor",
+        about = "Vendor dependencies into the workspace.",
+        long_about = "Copy the installed contents of all locked dependencies into the workspace, so that they can be resolved without registry access.",
+        next_help_heading = HEADING_PKG_MANAGEMENT,
+    )]
+    Vendor(VendorArgs),
 }
 
 #[derive(Clone, Debug, Parser, State)]
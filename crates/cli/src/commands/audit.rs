@@ -0,0 +1,116 @@
+use clap::Args;
+use espresso_audit::{AdvisoryDatabase, AdvisoryFinding, AdvisorySeverity, AuditError};
+use espresso_lockfile::Lockfile;
+use espresso_store::Store;
+use espresso_workspace::Workspace;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug)]
+pub struct AuditArgs {
+    #[arg(
+        long,
+        env = "ESPM_ADVISORY_DB",
+        help = "Path to a local advisory database. Defaults to the advisories directory within the espresso store root (~/.espresso, or ESPM_ROOT)."
+    )]
+    pub db: Option<PathBuf>,
+
+    #[arg(long, help = "Print findings as JSON.")]
+    pub json: bool,
+
+    #[arg(
+        long,
+        env = "ESPM_ADVISORY_REPO",
+        help = "Git repository to clone the advisory database from."
+    )]
+    pub repo: Option<String>,
+
+    #[arg(
+        value_enum,
+        short = 's',
+        long,
+        env = "ESPM_AUDIT_SEVERITY",
+        help = "Minimum severity that will fail the audit.",
+        default_value_t
+    )]
+    pub severity: AdvisorySeverity,
+
+    #[arg(long, help = "Update the advisory database before auditing.")]
+    pub update: bool,
+}
+
+#[derive(Serialize)]
+struct AuditOutput<'a> {
+    findings: &'a [AdvisoryFinding],
+    severity: AdvisorySeverity,
+    vulnerable: usize,
+}
+
+#[system]
+pub async fn audit(
+    args: ArgsRef<AuditArgs>,
+    workspace: ResourceRef<Workspace>,
+    store: ResourceRef<Store>,
+) {
+    let db_dir = args
+        .db
+        .clone()
+        .unwrap_or_else(|| store.root.join("advisories"));
+
+    if args.update {
+        AdvisoryDatabase::update(&db_dir, args.repo.as_deref()).await?;
+    }
+
+    let db = AdvisoryDatabase::load_from(&db_dir)?;
+    let lockfile = Lockfile::load(&workspace.root)?;
+    let findings = db.audit(&lockfile);
+    let vulnerable = findings
+        .iter()
+        .filter(|finding| finding.advisory.severity >= args.severity)
+        .count();
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&AuditOutput {
+                findings: &findings,
+                severity: args.severity,
+                vulnerable,
+            })
+            .into_diagnostic()?
+        );
+    } else if findings.is_empty() {
+        println!(
+            "No vulnerabilities found in {} locked packages",
+            lockfile.packages.len()
+        );
+    } else {
+        for finding in &findings {
+            let severity = finding.advisory.severity.to_string();
+
+            println!(
+                "{} {} {} {}",
+                color::id(finding.package.as_str()),
+                color::symbol(finding.version.to_string()),
+                if finding.advisory.severity >= args.severity {
+                    color::failure(severity)
+                } else {
+                    color::muted_light(severity)
+                },
+                color::muted(format!("({})", finding.advisory.id)),
+            );
+            println!("  {}", finding.advisory.description.trim());
+        }
+    }
+
+    if vulnerable > 0 {
+        return Err(AuditError::VulnerabilitiesFound {
+            count: vulnerable,
+            severity: args.severity,
+        }
+        .into());
+    }
+}
@@ -1,25 +1,340 @@
 use crate::app::GlobalArgs;
-use crate::helpers::loop_packages;
-use clap::Args;
-use espresso_common::EsTarget;
-use espresso_compiler::Compiler;
+use crate::helpers::{
+    load_workspace_packages, log_package, print_error_json, run_packages, start_checkpoint,
+    FileWatcher,
+};
+use clap::{Args, ValueEnum};
+use espresso_common::{EsTarget, PackageName};
+use espresso_compiler::{get_default_concurrency, BuildReport, Compiler, WorkspacePackages};
+use espresso_manifest::MANIFEST_NAME;
+use espresso_package::Package;
 use espresso_store::Store;
-use espresso_workspace::Workspace;
+use espresso_workspace::{PackageGraph, SelectQuery, Workspace};
+use miette::IntoDiagnostic;
+use notify::RecursiveMode;
 use starbase::system;
 use starbase_styles::color;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
+use tracing::debug;
 
 #[derive(Args, Clone, Debug)]
 pub struct BuildArgs {
     #[arg(
         value_enum,
         short = 't',
-        long,
+        long = "target",
         env = "ESPM_TARGET",
-        help = "ECMAScript target to transform source code to.",
-        default_value_t
+        value_delimiter = ',',
+        help = "ECMAScript target to transform source code to. Can be passed multiple times.",
+        conflicts_with = "all_targets"
     )]
-    pub target: EsTarget,
+    pub targets: Vec<EsTarget>,
+
+    #[arg(long, help = "Build all ECMAScript targets.")]
+    pub all_targets: bool,
+
+    #[arg(
+        short = 'c',
+        long,
+        env = "ESPM_CONCURRENCY",
+        help = "Maximum number of files to compile in parallel, per package. Defaults to the number of cores."
+    )]
+    pub concurrency: Option<usize>,
+
+    #[arg(
+        short = 'j',
+        long,
+        env = "ESPM_JOBS",
+        help = "Maximum number of packages to build in parallel. Defaults to the number of cores."
+    )]
+    pub jobs: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Stream build reports to stdout as JSON, one per line, instead of a summary.",
+        conflicts_with = "watch"
+    )]
+    pub json: bool,
+
+    #[arg(long, help = "Continue building other packages when a package fails.")]
+    pub no_bail: bool,
+
+    #[arg(long, help = "Watch source files and rebuild packages when they change.")]
+    pub watch: bool,
+}
+
+impl BuildArgs {
+    /// Selected targets, deduplicated and sorted from oldest to newest.
+    pub fn get_targets(&self) -> Vec<EsTarget> {
+        if self.targets.is_empty() && !self.all_targets {
+            return vec![EsTarget::default()];
+        }
+
+        EsTarget::value_variants()
+            .iter()
+            .filter(|target| self.all_targets || self.targets.contains(target))
+            .copied()
+            .collect()
+    }
+}
+
+fn create_compiler<'pkg>(
+    package: &'pkg Package,
+    args: &BuildArgs,
+    store: &Arc<Store>,
+    workspace_packages: &Arc<WorkspacePackages>,
+) -> miette::Result<Compiler<'pkg>> {
+    let mut compiler = Compiler::new(package, Arc::clone(store))?;
+    compiler.workspace_packages = Arc::clone(workspace_packages);
+
+    if let Some(concurrency) = args.concurrency {
+        compiler.concurrency = concurrency;
+    }
+
+    Ok(compiler)
+}
+
+fn format_size(size: u64) -> String {
+    if size < 1024 {
+        format!("{size} B")
+    } else {
+        format!("{:.2} KiB", size as f64 / 1024.0)
+    }
+}
+
+fn print_report(package: &Package, report: &BuildReport) {
+    let (size, gzip_size, brotli_size) = report.get_total_size();
+    let mut rows = vec![(
+        "File".to_owned(),
+        "Size".to_owned(),
+        "Gzip".to_owned(),
+        "Brotli".to_owned(),
+    )];
+
+    for file in &report.files {
+        rows.push((
+            file.path.to_string(),
+            format_size(file.size),
+            format_size(file.gzip_size),
+            format_size(file.brotli_size),
+        ));
+    }
+
+    rows.push((
+        "Total".to_owned(),
+        format_size(size),
+        format_size(gzip_size),
+        format_size(brotli_size),
+    ));
+
+    let file_width = rows.iter().map(|row| row.0.len()).max().unwrap_or_default();
+    let last_index = rows.len() - 1;
+
+    for (index, (file, size, gzip_size, brotli_size)) in rows.into_iter().enumerate() {
+        let line = format!("{file:<file_width$}  {size:>10}  {gzip_size:>10}  {brotli_size:>10}");
+
+        log_package(
+            package,
+            if index == 0 || index == last_index {
+                color::muted_light(line)
+            } else {
+                line
+            },
+        );
+    }
+
+    log_package(
+        package,
+        format!(
+            "Built to {} in {}ms",
+            color::path(&report.out_dir),
+            report.timings.total
+        ),
+    );
+}
+
+async fn rebuild_package(
+    package: &Package,
+    args: &BuildArgs,
+    store: &Arc<Store>,
+    workspace_packages: &Arc<WorkspacePackages>,
+    files: &[PathBuf],
+) -> miette::Result<()> {
+    start_checkpoint(package.name());
+
+    let compiler = create_compiler(package, args, store, workspace_packages)?;
+    let targets = args.get_targets();
+
+    let reports = if files.is_empty() {
+        // The cache only tracks this package's own files, so clear it to ensure
+        // that everything (including declarations) is compiled again
+        compiler.clear_cache(&targets)?;
+        compiler.compile_targets(&targets).await?
+    } else {
+        for file in files {
+            log_package(package, format!("Changed {}", color::path(file)));
+        }
+
+        let mut reports = vec![];
+
+        for target in targets {
+            reports.push(compiler.compile_files(target, files).await?);
+        }
+
+        reports
+    };
+
+    for report in reports {
+        log_package(
+            package,
+            format!(
+                "Rebuilt to {} in {}ms",
+                color::path(&report.out_dir),
+                report.timings.total
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+/// Rebuild the selected packages that own a changed path, and their dependents.
+async fn rebuild_changed_packages(
+    workspace: &Workspace,
+    names: &Vec<PackageName>,
+    paths: &[PathBuf],
+    args: &BuildArgs,
+    store: &Arc<Store>,
+) -> miette::Result<()> {
+    let packages = workspace.select_packages(SelectQuery {
+        names: Some(names),
+        ..SelectQuery::default()
+    })?;
+    let graph = PackageGraph::new(workspace.load_packages()?);
+    let workspace_packages = load_workspace_packages(workspace)?;
+    let root_manifest = workspace.root.join(MANIFEST_NAME);
+
+    let mut changed_files: BTreeMap<&PackageName, BTreeSet<PathBuf>> = BTreeMap::new();
+    let mut changed_manifests = HashSet::new();
+
+    for path in paths {
+        for package in &packages {
+            let name = &package.manifest.package.name;
+
+            // Workspace settings are inherited by every package
+            if *path == root_manifest || *path == package.root.join(MANIFEST_NAME) {
+                changed_manifests.insert(name);
+            } else if path.starts_with(&package.src_dir) {
+                changed_files.entry(name).or_default().insert(path.clone());
+            }
+        }
+    }
+
+    // Dependents must also be rebuilt in full, in graph order
+    let mut rebuild_all = changed_manifests.clone();
+
+    for name in changed_files.keys().chain(changed_manifests.iter()) {
+        rebuild_all.extend(graph.get_dependents(name)?);
+    }
+
+    // Packages are already sorted topologically
+    for package in &packages {
+        let name = &package.manifest.package.name;
+
+        let result = if rebuild_all.contains(name) {
+            rebuild_package(package, args, store, &workspace_packages, &[]).await
+        } else if let Some(files) = changed_files.get(name) {
+            rebuild_package(
+                package,
+                args,
+                store,
+                &workspace_packages,
+                &files.iter().cloned().collect::<Vec<_>>(),
+            )
+            .await
+        } else {
+            continue;
+        };
+
+        // Don't exit on failure, so that the error can be fixed. Failures
+        // have already been printed when not bailing.
+        if let Err(error) = result {
+            if !args.no_bail {
+                eprintln!("{error:?}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn watch_packages(
+    workspace: &Workspace,
+    packages: Vec<&Package>,
+    args: &BuildArgs,
+    store: Arc<Store>,
+) -> miette::Result<()> {
+    let mut watcher = FileWatcher::new()?;
+
+    if workspace.monorepo {
+        watcher.watch(
+            &workspace.root.join(MANIFEST_NAME),
+            RecursiveMode::NonRecursive,
+        )?;
+    }
+
+    for package in &packages {
+        debug!(package = package.name(), src_dir = ?package.src_dir, "Watching package");
+
+        watcher.watch(&package.src_dir, RecursiveMode::Recursive)?;
+
+        watcher.watch(
+            &package.root.join(MANIFEST_NAME),
+            RecursiveMode::NonRecursive,
+        )?;
+    }
+
+    // Packages are loaded again when a manifest changes, so track them by name
+    let names = packages
+        .iter()
+        .map(|package| package.manifest.package.name.clone())
+        .collect::<Vec<_>>();
+    let mut reloaded_workspace = None;
+
+    while let Some(paths) = watcher.next_changes().await {
+        // Settings and dependencies may have changed, so load the workspace again
+        if paths
+            .iter()
+            .any(|path| path.file_name().is_some_and(|name| name == MANIFEST_NAME))
+        {
+            match Workspace::load_from(&workspace.working_dir) {
+                Ok(reloaded) => {
+                    reloaded_workspace = Some(reloaded);
+                }
+                Err(error) => {
+                    eprintln!("{error:?}");
+                    continue;
+                }
+            }
+        }
+
+        // Don't exit on failure, so that the error can be fixed
+        if let Err(error) = rebuild_changed_packages(
+            reloaded_workspace.as_ref().unwrap_or(workspace),
+            &names,
+            &paths,
+            args,
+            &store,
+        )
+        .await
+        {
+            eprintln!("{error:?}");
+        }
+    }
+
+    Ok(())
 }
 
 #[system]
@@ -32,18 +347,75 @@ pub async fn build(
     let store = Arc::new(store.to_owned());
     let packages = workspace.select_packages(global_args.to_package_select_query())?;
 
-    loop_packages(packages, |package| async {
-        println!("Building target {}", color::symbol(args.target.to_string()));
+    let workspace_packages = load_workspace_packages(workspace)?;
+    let targets = args.get_targets();
+    let jobs = args.jobs.unwrap_or_else(get_default_concurrency);
+
+    let result = run_packages(
+        workspace,
+        packages.clone(),
+        jobs,
+        !args.no_bail,
+        |package| async {
+            if !args.json {
+                start_checkpoint(package.name());
+
+                log_package(
+                    package,
+                    format!(
+                        "Building {} {}",
+                        if targets.len() == 1 {
+                            "target"
+                        } else {
+                            "targets"
+                        },
+                        targets
+                            .iter()
+                            .map(|target| color::symbol(target.to_string()))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                );
+            }
+
+            let reports = match create_compiler(package, args, &store, &workspace_packages)?
+                .compile_targets(&targets)
+                .await
+            {
+                Ok(reports) => reports,
+                Err(error) => {
+                    if args.json {
+                        print_error_json(package, &error);
+                    }
+
+                    return Err(error);
+                }
+            };
 
-        let out_dir = Compiler::new(package, Arc::clone(&store))?
-            .compile(args.target)
-            .await?;
+            for report in reports {
+                if args.json {
+                    println!("{}", serde_json::to_string(&report).into_diagnostic()?);
+                } else {
+                    print_report(package, &report);
+                }
+            }
 
-        package.copy_info_files(&out_dir)?;
+            Ok(())
+        },
+    )
+    .await;
 
-        println!("Built to {}", color::path(out_dir));
+    if args.watch {
+        // Don't exit on failure, so that the error can be fixed. Failures
+        // have already been printed when not bailing.
+        if let Err(error) = result {
+            if !args.no_bail {
+                eprintln!("{error:?}");
+            }
+        }
 
-        Ok(())
-    })
-    .await?;
+        watch_packages(workspace, packages, args, store).await?;
+    } else {
+        result?;
+    }
 }
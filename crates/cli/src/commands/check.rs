@@ -0,0 +1,258 @@
+use crate::app::GlobalArgs;
+use crate::helpers::{
+    load_workspace_packages, log_package, print_error_json, run_packages, start_checkpoint,
+    FileWatcher,
+};
+use clap::Args;
+use espresso_common::{EsTarget, PackageName};
+use espresso_compiler::{get_default_concurrency, Declarations, WorkspacePackages, OUT_DIR};
+use espresso_manifest::MANIFEST_NAME;
+use espresso_package::Package;
+use espresso_store::Store;
+use espresso_workspace::{PackageGraph, SelectQuery, Workspace};
+use notify::RecursiveMode;
+use starbase::system;
+use starbase_styles::color;
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct CheckArgs {
+    #[arg(
+        value_enum,
+        short = 't',
+        long,
+        env = "ESPM_TARGET",
+        help = "ECMAScript target to type check against.",
+        default_value_t
+    )]
+    pub target: EsTarget,
+
+    #[arg(
+        short = 'j',
+        long,
+        env = "ESPM_JOBS",
+        help = "Maximum number of packages to type check in parallel. Defaults to the number of cores."
+    )]
+    pub jobs: Option<usize>,
+
+    #[arg(
+        long,
+        help = "Print failures to stdout as JSON, one per line, instead of rendering them.",
+        conflicts_with = "watch"
+    )]
+    pub json: bool,
+
+    #[arg(
+        long,
+        help = "Watch source and test files and type check packages when they change."
+    )]
+    pub watch: bool,
+}
+
+async fn check_package(
+    package: &Package,
+    args: &CheckArgs,
+    store: &Arc<Store>,
+    workspace_packages: &Arc<WorkspacePackages>,
+) -> miette::Result<()> {
+    let start = Instant::now();
+
+    let mut declarations = Declarations::new(
+        package.root.clone(),
+        package.root.join(OUT_DIR).join(args.target.to_string()),
+        Arc::new(package.manifest.build.clone()),
+        Arc::clone(store),
+    );
+
+    // Resolve imports of sibling packages to their sources
+    if let Some(workspace_package) = workspace_packages.get(&package.manifest.package.name) {
+        declarations.dependencies = workspace_package.dependencies.clone();
+        declarations.workspace_packages = Arc::clone(workspace_packages);
+    }
+
+    declarations.check(&args.target).await?;
+
+    if !args.json {
+        log_package(
+            package,
+            format!("Type checked in {}ms", start.elapsed().as_millis()),
+        );
+    }
+
+    Ok(())
+}
+
+/// Type check the selected packages that own a changed path, and their dependents.
+async fn check_changed_packages(
+    workspace: &Workspace,
+    names: &Vec<PackageName>,
+    paths: &[PathBuf],
+    args: &CheckArgs,
+    store: &Arc<Store>,
+) -> miette::Result<()> {
+    let packages = workspace.select_packages(SelectQuery {
+        names: Some(names),
+        ..SelectQuery::default()
+    })?;
+    let graph = PackageGraph::new(workspace.load_packages()?);
+    let workspace_packages = load_workspace_packages(workspace)?;
+    let root_manifest = workspace.root.join(MANIFEST_NAME);
+
+    let mut changed: BTreeSet<&PackageName> = BTreeSet::new();
+
+    for path in paths {
+        for package in &packages {
+            // Workspace settings are inherited by every package
+            if *path == root_manifest
+                || *path == package.root.join(MANIFEST_NAME)
+                || path.starts_with(&package.src_dir)
+                || path.starts_with(&package.tests_dir)
+            {
+                changed.insert(&package.manifest.package.name);
+            }
+        }
+    }
+
+    // Dependents may be affected by changed types
+    for name in changed.clone() {
+        changed.extend(graph.get_dependents(name)?);
+    }
+
+    // Packages are already sorted topologically
+    for package in &packages {
+        if !changed.contains(&package.manifest.package.name) {
+            continue;
+        }
+
+        start_checkpoint(package.name());
+
+        // Don't exit on failure, so that the error can be fixed
+        if let Err(error) = check_package(package, args, store, &workspace_packages).await {
+            eprintln!("{error:?}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn watch_packages(
+    workspace: &Workspace,
+    packages: Vec<&Package>,
+    args: &CheckArgs,
+    store: Arc<Store>,
+) -> miette::Result<()> {
+    let mut watcher = FileWatcher::new()?;
+
+    if workspace.monorepo {
+        watcher.watch(
+            &workspace.root.join(MANIFEST_NAME),
+            RecursiveMode::NonRecursive,
+        )?;
+    }
+
+    for package in &packages {
+        debug!(package = package.name(), root = ?package.root, "Watching package");
+
+        watcher.watch(&package.src_dir, RecursiveMode::Recursive)?;
+
+        if package.tests_dir.exists() {
+            watcher.watch(&package.tests_dir, RecursiveMode::Recursive)?;
+        }
+
+        watcher.watch(
+            &package.root.join(MANIFEST_NAME),
+            RecursiveMode::NonRecursive,
+        )?;
+    }
+
+    // Packages are loaded again when a manifest changes, so track them by name
+    let names = packages
+        .iter()
+        .map(|package| package.manifest.package.name.clone())
+        .collect::<Vec<_>>();
+    let mut reloaded_workspace = None;
+
+    while let Some(paths) = watcher.next_changes().await {
+        // Settings and dependencies may have changed, so load the workspace again
+        if paths
+            .iter()
+            .any(|path| path.file_name().is_some_and(|name| name == MANIFEST_NAME))
+        {
+            match Workspace::load_from(&workspace.working_dir) {
+                Ok(reloaded) => {
+                    reloaded_workspace = Some(reloaded);
+                }
+                Err(error) => {
+                    eprintln!("{error:?}");
+                    continue;
+                }
+            }
+        }
+
+        // Don't exit on failure, so that the error can be fixed
+        if let Err(error) = check_changed_packages(
+            reloaded_workspace.as_ref().unwrap_or(workspace),
+            &names,
+            &paths,
+            args,
+            &store,
+        )
+        .await
+        {
+            eprintln!("{error:?}");
+        }
+    }
+
+    Ok(())
+}
+
+#[system]
+pub async fn check(
+    args: ArgsRef<CheckArgs>,
+    global_args: StateRef<GlobalArgs>,
+    workspace: ResourceRef<Workspace>,
+    store: ResourceRef<Store>,
+) {
+    let store = Arc::new(store.to_owned());
+    let packages = workspace.select_packages(global_args.to_package_select_query())?;
+    let workspace_packages = load_workspace_packages(workspace)?;
+    let jobs = args.jobs.unwrap_or_else(get_default_concurrency);
+
+    // Type checking has no side effects, so check every package
+    let result = run_packages(workspace, packages.clone(), jobs, false, |package| async {
+        if !args.json {
+            start_checkpoint(package.name());
+
+            log_package(
+                package,
+                format!(
+                    "Type checking against target {}",
+                    color::symbol(args.target.to_string())
+                ),
+            );
+        }
+
+        let result = check_package(package, args, &store, &workspace_packages).await;
+
+        if args.json {
+            if let Err(error) = &result {
+                print_error_json(package, error);
+            }
+        }
+
+        result
+    })
+    .await;
+
+    // Failures have already been printed, and
+    // watching continues so that they can be fixed
+    if args.watch {
+        watch_packages(workspace, packages, args, store).await?;
+    } else {
+        result?;
+    }
+}
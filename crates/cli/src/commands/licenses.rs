@@ -0,0 +1,146 @@
+use clap::Args;
+use espresso_common::{LicenseType, PackageName, Version};
+use espresso_lockfile::Lockfile;
+use espresso_manifest::{ManifestError, MANIFEST_NAME};
+use espresso_package::Package;
+use espresso_store::{PackageItem, Store};
+use espresso_workspace::Workspace;
+use miette::IntoDiagnostic;
+use serde::Serialize;
+use starbase::system;
+use starbase_styles::color;
+use tracing::warn;
+
+#[derive(Args, Clone, Debug)]
+pub struct LicensesArgs {
+    #[arg(long, help = "Print licenses as JSON.")]
+    pub json: bool,
+}
+
+#[derive(Serialize)]
+struct LicenseEntry {
+    allowed: bool,
+    license: Option<LicenseType>,
+    mismatch: bool,
+    name: PackageName,
+    version: Option<Version>,
+    workspace: bool,
+}
+
+fn create_entry(package: &Package, workspace: bool, allowed: bool) -> miette::Result<LicenseEntry> {
+    let mismatch = package.has_license_mismatch()?;
+
+    if mismatch {
+        warn!(
+            package = package.name(),
+            "Declared license does not match the contents of the license file",
+        );
+    }
+
+    Ok(LicenseEntry {
+        allowed,
+        license: package.manifest.package.license.clone(),
+        mismatch,
+        name: package.manifest.package.name.clone(),
+        version: package.manifest.package.version.clone(),
+        workspace,
+    })
+}
+
+#[system]
+pub async fn licenses(
+    args: ArgsRef<LicensesArgs>,
+    workspace: ResourceRef<Workspace>,
+    store: ResourceRef<Store>,
+) {
+    let policy = workspace.manifest.get_license_settings();
+    let install = workspace.manifest.get_install_settings();
+    let mut entries = vec![];
+
+    // Workspace packages are not subject to the policy,
+    // but we still list them and verify their license files
+    for package in workspace.load_packages()?.values() {
+        entries.push(create_entry(package, true, true)?);
+    }
+
+    // Locked dependencies are read from the store, as their
+    // manifests are only available once they've been installed
+    for locked in Lockfile::load(&workspace.root)?.packages {
+        let package_dir = store.get_item_dir(&PackageItem {
+            minified: install.minify,
+            package: &locked.name,
+            target: &install.target,
+            version: &locked.version,
+        });
+
+        if package_dir.join(MANIFEST_NAME).exists() {
+            let package = Package::new(&package_dir)?;
+            let allowed = package
+                .manifest
+                .package
+                .license
+                .as_ref()
+                .map(|license| policy.is_allowed(license))
+                .unwrap_or(policy.allow.is_empty());
+
+            entries.push(create_entry(&package, false, allowed)?);
+        } else {
+            warn!(
+                package = locked.name.as_str(),
+                "Package has not been installed, unable to determine its license",
+            );
+
+            entries.push(LicenseEntry {
+                allowed: policy.allow.is_empty(),
+                license: None,
+                mismatch: false,
+                name: locked.name,
+                version: Some(locked.version),
+                workspace: false,
+            });
+        }
+    }
+
+    let not_allowed = entries.iter().filter(|entry| !entry.allowed).count();
+
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&entries).into_diagnostic()?
+        );
+    } else {
+        for entry in &entries {
+            let license = entry
+                .license
+                .as_ref()
+                .map(|license| license.to_string())
+                .unwrap_or_else(|| "unknown".into());
+
+            println!(
+                "{} {} {}{}",
+                color::id(entry.name.as_str()),
+                color::symbol(
+                    entry
+                        .version
+                        .as_ref()
+                        .map(|version| version.to_string())
+                        .unwrap_or_default()
+                ),
+                if entry.allowed {
+                    color::muted_light(license)
+                } else {
+                    color::failure(license)
+                },
+                if entry.workspace {
+                    color::muted(" (workspace)")
+                } else {
+                    String::new()
+                },
+            );
+        }
+    }
+
+    if not_allowed > 0 {
+        return Err(ManifestError::LicensesNotAllowed { count: not_allowed }.into());
+    }
+}
@@ -1,9 +1,21 @@
+mod audit;
 mod build;
+mod check;
 mod debug;
 mod init;
+mod licenses;
 mod new;
+mod sbom;
+mod sync;
+mod vendor;
 
+pub use audit::*;
 pub use build::*;
+pub use check::*;
 pub use debug::*;
 pub use init::*;
+pub use licenses::*;
 pub use new::*;
+pub use sbom::*;
+pub use sync::*;
+pub use vendor::*;
@@ -0,0 +1,55 @@
+use crate::app::GlobalArgs;
+use clap::Args;
+use espresso_sbom::{Sbom, SbomFormat};
+use espresso_store::Store;
+use espresso_workspace::Workspace;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::fs;
+use std::path::PathBuf;
+
+#[derive(Args, Clone, Debug)]
+pub struct SbomArgs {
+    #[arg(long, help = "Include dev dependencies of the selected packages.")]
+    pub dev: bool,
+
+    #[arg(
+        value_enum,
+        long,
+        env = "ESPM_SBOM_FORMAT",
+        help = "Format of the bill of materials.",
+        default_value_t
+    )]
+    pub format: SbomFormat,
+
+    #[arg(
+        short = 'o',
+        long,
+        help = "File to write the bill of materials to. Defaults to stdout."
+    )]
+    pub output: Option<PathBuf>,
+}
+
+#[system]
+pub async fn sbom(
+    args: ArgsRef<SbomArgs>,
+    global_args: StateRef<GlobalArgs>,
+    workspace: ResourceRef<Workspace>,
+    store: ResourceRef<Store>,
+) {
+    let packages = workspace.select_packages(global_args.to_package_select_query())?;
+    let contents = Sbom::generate(workspace, store, &packages, args.dev)?.render(args.format)?;
+
+    match &args.output {
+        Some(output) => {
+            let output = workspace.working_dir.join(output);
+
+            fs::write_file(&output, contents)?;
+
+            println!("Wrote bill of materials to {}", color::path(output));
+        }
+        None => {
+            println!("{contents}");
+        }
+    };
+}
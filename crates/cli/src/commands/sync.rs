@@ -0,0 +1,169 @@
+use crate::helpers::{load_workspace_packages, log_package, FileWatcher};
+use clap::{Args, Subcommand};
+use espresso_common::EsTarget;
+use espresso_compiler::{
+    create_solution_tsconfig, is_generated_tsconfig, CompilerError, Declarations, OUT_DIR,
+};
+use espresso_manifest::MANIFEST_NAME;
+use espresso_store::Store;
+use espresso_workspace::Workspace;
+use notify::RecursiveMode;
+use starbase::system;
+use starbase_styles::color;
+use std::sync::Arc;
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct SyncArgs {
+    #[command(subcommand)]
+    pub command: SyncCommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum SyncCommands {
+    #[command(
+        name = "tsconfig",
+        about = "Sync tsconfig.json files for editors.",
+        long_about = "Write a `tsconfig.json` to the root of each package, with the same compiler options used to build it, and a solution-style `tsconfig.json` to the workspace root."
+    )]
+    TsConfig(SyncTsConfigArgs),
+}
+
+#[derive(Args, Clone, Debug)]
+pub struct SyncTsConfigArgs {
+    #[arg(
+        value_enum,
+        short = 't',
+        long,
+        env = "ESPM_TARGET",
+        help = "ECMAScript target to configure the compiler options for.",
+        default_value_t
+    )]
+    pub target: EsTarget,
+
+    #[arg(
+        long,
+        help = "Overwrite tsconfig.json files that were not generated by espm."
+    )]
+    pub force: bool,
+
+    #[arg(
+        long,
+        help = "Watch manifests and sync tsconfig.json files again when they change."
+    )]
+    pub watch: bool,
+}
+
+async fn sync_workspace(
+    workspace: &Workspace,
+    args: &SyncTsConfigArgs,
+    store: &Arc<Store>,
+) -> miette::Result<()> {
+    let packages = workspace.load_packages()?;
+    let workspace_packages = load_workspace_packages(workspace)?;
+
+    // Don't overwrite files that were written by hand, unless forced,
+    // and check them all before writing, so that nothing is partially synced
+    if !args.force {
+        let mut tsconfig_files = packages
+            .values()
+            .map(|package| package.root.join("tsconfig.json"))
+            .collect::<Vec<_>>();
+
+        if workspace.monorepo {
+            tsconfig_files.push(workspace.root.join("tsconfig.json"));
+        }
+
+        for path in tsconfig_files {
+            if !is_generated_tsconfig(&path)? {
+                return Err(CompilerError::TsConfigNotGenerated { path })?;
+            }
+        }
+    }
+
+    for package in packages.values() {
+        let mut declarations = Declarations::new(
+            package.root.clone(),
+            package.root.join(OUT_DIR).join(args.target.to_string()),
+            Arc::new(package.manifest.build.clone()),
+            Arc::clone(store),
+        );
+
+        if let Some(workspace_package) = workspace_packages.get(&package.manifest.package.name) {
+            declarations.dependencies = workspace_package.dependencies.clone();
+            declarations.workspace_packages = Arc::clone(&workspace_packages);
+        }
+
+        let ts_version = declarations.resolve_typescript_version().await?;
+
+        declarations.create_editor_tsconfig(&args.target, &ts_version)?;
+
+        log_package(package, format!("Synced {}", color::file("tsconfig.json")));
+    }
+
+    if workspace.monorepo {
+        create_solution_tsconfig(
+            &workspace.root,
+            &packages
+                .values()
+                .map(|package| package.root.as_path())
+                .collect::<Vec<_>>(),
+        )?;
+
+        println!("Synced workspace {}", color::file("tsconfig.json"));
+    }
+
+    Ok(())
+}
+
+async fn watch_workspace(
+    workspace: &Workspace,
+    args: &SyncTsConfigArgs,
+    store: Arc<Store>,
+) -> miette::Result<()> {
+    // Ignore the tsconfig.json files that we write
+    let mut watcher = FileWatcher::with_filter(|path| {
+        path.file_name()
+            .is_some_and(|name| name == MANIFEST_NAME || name == "tsconfig.espm.json")
+    })?;
+
+    // Watch the directories instead of the files, as a
+    // tsconfig.espm.json may be created after starting
+    watcher.watch(&workspace.root, RecursiveMode::NonRecursive)?;
+
+    for package in workspace.load_packages()?.values() {
+        debug!(package = package.name(), root = ?package.root, "Watching package");
+
+        watcher.watch(&package.root, RecursiveMode::NonRecursive)?;
+    }
+
+    while watcher.next_changes().await.is_some() {
+        // Dependencies and settings may have changed, so load the workspace again
+        let result = match Workspace::load_from(&workspace.working_dir) {
+            Ok(reloaded) => sync_workspace(&reloaded, args, &store).await,
+            Err(error) => Err(error),
+        };
+
+        // Don't exit on failure, so that the error can be fixed
+        if let Err(error) = result {
+            eprintln!("{error:?}");
+        }
+    }
+
+    Ok(())
+}
+
+#[system]
+pub async fn sync_tsconfig(
+    args: ArgsRef<SyncTsConfigArgs>,
+    workspace: ResourceRef<Workspace>,
+    store: ResourceRef<Store>,
+) {
+    let store = Arc::new(store.to_owned());
+
+    sync_workspace(workspace, args, &store).await?;
+
+    if args.watch {
+        watch_workspace(workspace, args, store).await?;
+    }
+}
@@ -0,0 +1,135 @@
+use clap::Args;
+use espresso_lockfile::Lockfile;
+use espresso_manifest::MANIFEST_NAME;
+use espresso_store::{PackageItem, StorageItem, Store, StoreError};
+use espresso_workspace::Workspace;
+use miette::IntoDiagnostic;
+use relative_path::RelativePathBuf;
+use starbase::system;
+use starbase_styles::color;
+use starbase_utils::{fs, glob};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+#[derive(Args, Clone, Debug)]
+pub struct VendorArgs {
+    #[arg(help = "Directory to vendor dependencies into, relative from the workspace root.")]
+    pub dir: Option<RelativePathBuf>,
+}
+
+// Vendored packages are laid out as `<namespace>/<name>/v<version>/<flavor>`,
+// so only remove package directories that are no longer used, and the
+// parent directories they leave empty.
+fn prune_vendor_dir(vendor_dir: &Path, vendored_dirs: &HashSet<PathBuf>) -> miette::Result<()> {
+    for dir in glob::walk(vendor_dir, ["*/*/v*/*"])? {
+        if !dir.is_dir() || vendored_dirs.contains(&dir) {
+            continue;
+        }
+
+        debug!(dir = ?dir, "Removing stale vendored package");
+
+        fs::remove_dir_all(&dir)?;
+
+        let mut parent = dir.parent();
+
+        while let Some(parent_dir) = parent {
+            if parent_dir == vendor_dir || !fs::read_dir(parent_dir)?.is_empty() {
+                break;
+            }
+
+            fs::remove_dir_all(parent_dir)?;
+            parent = parent_dir.parent();
+        }
+    }
+
+    Ok(())
+}
+
+#[system]
+pub async fn vendor(
+    args: ArgsRef<VendorArgs>,
+    workspace: ResourceRef<Workspace>,
+    store: ResourceRef<Store>,
+) {
+    let install = workspace.manifest.get_install_settings();
+    let rel_vendor_dir = args
+        .dir
+        .clone()
+        .or_else(|| install.vendor_dir.clone())
+        .unwrap_or_else(|| RelativePathBuf::from("vendor"));
+    let vendor_dir = rel_vendor_dir.to_logical_path(&workspace.root);
+    let lockfile = Lockfile::load(&workspace.root)?;
+    let mut vendored_dirs = HashSet::new();
+
+    for locked in &lockfile.packages {
+        let item = PackageItem {
+            minified: install.minify,
+            package: &locked.name,
+            target: &install.target,
+            version: &locked.version,
+        };
+        // Always copy from the store, even when already vendored
+        let source_dir = store.get_stored_item_dir(&item);
+        let dest_dir = vendor_dir.join(item.to_file_path());
+
+        vendored_dirs.insert(dest_dir.clone());
+
+        if !source_dir.exists() {
+            // Already vendored and the store has since been cleaned
+            if dest_dir.exists() {
+                continue;
+            }
+
+            return Err(StoreError::MissingItem {
+                label: item.get_label().to_owned(),
+                dir: source_dir,
+            }
+            .into());
+        }
+
+        debug!(
+            package = locked.name.as_str(),
+            source_dir = ?source_dir,
+            dest_dir = ?dest_dir,
+            "Vendoring package",
+        );
+
+        if dest_dir.exists() {
+            fs::remove_dir_all(&dest_dir)?;
+        }
+
+        for file in glob::walk_files(&source_dir, ["**/*"])? {
+            // Ignore lock files from the store
+            if file.file_name().is_some_and(|name| name == ".lock") {
+                continue;
+            }
+
+            let rel_file = file.strip_prefix(&source_dir).into_diagnostic()?;
+
+            fs::copy_file(&file, dest_dir.join(rel_file))?;
+        }
+    }
+
+    // Packages that are no longer locked, or were vendored
+    // with different install settings, are no longer used
+    if vendor_dir.exists() {
+        prune_vendor_dir(&vendor_dir, &vendored_dirs)?;
+    }
+
+    // Persist the directory so that the store resolves from it first
+    let manifest_path = workspace.root.join(MANIFEST_NAME);
+    let mut manifest = fs::read_file(&manifest_path)?
+        .parse::<toml_edit::Document>()
+        .into_diagnostic()?;
+
+    manifest["install"]["vendor-dir"] = toml_edit::value(rel_vendor_dir.as_str());
+
+    fs::write_file(&manifest_path, manifest.to_string())?;
+
+    println!(
+        "Vendored {} packages to {}",
+        lockfile.packages.len(),
+        color::path(vendor_dir)
+    );
+}
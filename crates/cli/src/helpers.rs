@@ -1,8 +1,77 @@
 use console::{style, Style};
 use dialoguer::theme::ColorfulTheme;
+use espresso_compiler::{WorkspacePackage, WorkspacePackages};
 use espresso_package::Package;
-use starbase_styles::color::{create_style, Color, OwoStyle};
+use espresso_workspace::{PackageGraph, Workspace, WorkspaceError};
+use futures::stream::{FuturesUnordered, StreamExt};
+use miette::{Diagnostic, IntoDiagnostic};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde_json::{json, Value};
+use starbase_styles::color::{self, create_style, Color, OwoStyle};
+use std::collections::{BTreeMap, HashSet};
 use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+
+// Editors may emit multiple events for a single save,
+// so wait for events to settle before rebuilding
+pub const DEBOUNCE_DURATION: Duration = Duration::from_millis(100);
+
+/// Watches files and directories for changes, and batches
+/// the paths of events that occur in quick succession.
+pub struct FileWatcher {
+    receiver: mpsc::UnboundedReceiver<Vec<PathBuf>>,
+    watcher: RecommendedWatcher,
+}
+
+impl FileWatcher {
+    pub fn new() -> miette::Result<FileWatcher> {
+        Self::with_filter(|_| true)
+    }
+
+    /// Only report changes to paths that match the filter.
+    pub fn with_filter<F>(filter: F) -> miette::Result<FileWatcher>
+    where
+        F: Fn(&Path) -> bool + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let watcher = notify::recommended_watcher(move |result: notify::Result<Event>| {
+            if let Ok(mut event) = result {
+                event.paths.retain(|path| filter(path));
+
+                if !matches!(event.kind, EventKind::Access(_)) && !event.paths.is_empty() {
+                    let _ = sender.send(event.paths);
+                }
+            }
+        })
+        .into_diagnostic()?;
+
+        Ok(FileWatcher { receiver, watcher })
+    }
+
+    pub fn watch(&mut self, path: &Path, mode: RecursiveMode) -> miette::Result<()> {
+        self.watcher.watch(path, mode).into_diagnostic()
+    }
+
+    /// Wait for the next batch of changed paths, or return `None`
+    /// if the watcher has stopped.
+    pub async fn next_changes(&mut self) -> Option<Vec<PathBuf>> {
+        println!();
+        println!("Watching for changes...");
+
+        let mut paths = self.receiver.recv().await?;
+
+        while let Ok(Some(more_paths)) = timeout(DEBOUNCE_DURATION, self.receiver.recv()).await {
+            paths.extend(more_paths);
+        }
+
+        Some(paths)
+    }
+}
 
 pub fn create_theme() -> ColorfulTheme {
     ColorfulTheme {
@@ -63,27 +132,195 @@ pub fn start_checkpoint<T: AsRef<str>>(label: T) {
     );
 }
 
-pub async fn loop_packages<'pkg, F, Fut>(
+/// Prefix a line of output with the package name, so that
+/// output from packages running in parallel can be told apart.
+pub fn log_package<T: AsRef<str>>(package: &Package, message: T) {
+    println!(
+        "{} {}",
+        color::muted_light(format!("[{}]", package.name())),
+        message.as_ref()
+    );
+}
+
+/// Map every package in a multi package workspace to its direct workspace
+/// dependencies, so that TypeScript declarations can reference them.
+pub fn load_workspace_packages(workspace: &Workspace) -> miette::Result<Arc<WorkspacePackages>> {
+    let mut workspace_packages = BTreeMap::new();
+
+    if workspace.monorepo {
+        let packages = workspace.load_packages()?;
+        let graph = PackageGraph::new(packages);
+
+        for (name, package) in packages {
+            workspace_packages.insert(
+                name.to_owned(),
+                WorkspacePackage {
+                    build_settings: Arc::new(package.manifest.build.clone()),
+                    dependencies: graph
+                        .get_direct_dependencies(name)
+                        .into_iter()
+                        .cloned()
+                        .collect(),
+                    root: package.root.clone(),
+                },
+            );
+        }
+    }
+
+    Ok(Arc::new(workspace_packages))
+}
+
+/// Run a task for each package, as soon as all of its selected workspace dependencies
+/// have completed, with up to `concurrency` tasks running at once. When `bail` is false,
+/// failures are reported and other packages continue, except for those that depend
+/// on a failed package, which are skipped.
+pub async fn run_packages<'pkg, F, Fut>(
+    workspace: &'pkg Workspace,
     packages: Vec<&'pkg Package>,
+    concurrency: usize,
+    bail: bool,
     func: F,
 ) -> miette::Result<()>
 where
     F: Fn(&'pkg Package) -> Fut,
     Fut: Future<Output = miette::Result<()>>,
 {
-    let last_index = packages.len() - 1;
+    let graph = PackageGraph::new(workspace.load_packages()?);
+    let selected = packages
+        .iter()
+        .map(|package| &package.manifest.package.name)
+        .collect::<HashSet<_>>();
+
+    // Only wait on dependencies that are also being ran
+    let mut pending = vec![];
+
+    for package in packages {
+        let dependencies = graph
+            .get_dependencies(&package.manifest.package.name)?
+            .into_iter()
+            .filter(|name| selected.contains(name))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        pending.push((package, dependencies));
+    }
+
+    let mut completed = HashSet::new();
+    let mut failed = HashSet::new();
+    let mut errors = vec![];
+    let mut running = FuturesUnordered::new();
 
-    for (index, package) in packages.iter().enumerate() {
-        start_checkpoint(package.name());
+    loop {
+        // Start as many ready packages as possible, in topological order
+        while running.len() < concurrency.max(1) && (!bail || errors.is_empty()) {
+            let Some(index) = pending.iter().position(|(_, dependencies)| {
+                dependencies
+                    .iter()
+                    .all(|name| completed.contains(name) || failed.contains(name))
+            }) else {
+                break;
+            };
 
-        func(package).await?;
+            let (package, dependencies) = pending.remove(index);
+            let name = package.manifest.package.name.clone();
 
-        if index != last_index {
-            println!();
+            if dependencies.iter().any(|name| failed.contains(name)) {
+                eprintln!(
+                    "{} {}",
+                    color::muted_light(format!("[{}]", package.name())),
+                    color::muted("Skipped, as a dependency failed")
+                );
+                failed.insert(name);
+                continue;
+            }
+
+            let future = func(package);
+
+            running.push(async move { (name, future.await) });
         }
+
+        let Some((name, result)) = running.next().await else {
+            break;
+        };
+
+        match result {
+            Ok(_) => {
+                completed.insert(name);
+            }
+            Err(error) => {
+                if !bail {
+                    eprintln!("{error:?}");
+                }
+
+                failed.insert(name);
+                errors.push(error);
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    if bail || errors.len() == 1 {
+        return Err(errors.remove(0));
     }
 
-    Ok(())
+    Err(WorkspaceError::PackagesFailed {
+        errors: errors.into_iter().map(|error| error.into()).collect(),
+    }
+    .into())
+}
+
+// Editors and CI annotators can't parse rendered diagnostics,
+// so serialize them with file locations instead
+pub fn diagnostic_to_json(diagnostic: &dyn Diagnostic) -> Value {
+    let source = diagnostic.source_code();
+
+    let labels = diagnostic
+        .labels()
+        .map(|labels| {
+            labels
+                .map(|label| {
+                    let contents =
+                        source.and_then(|source| source.read_span(label.inner(), 0, 0).ok());
+
+                    json!({
+                        "message": label.label(),
+                        "file": contents.as_ref().and_then(|contents| contents.name()),
+                        "line": contents.as_ref().map(|contents| contents.line() + 1),
+                        "column": contents.as_ref().map(|contents| contents.column() + 1),
+                        "offset": label.offset(),
+                        "length": label.len(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+
+    let related = diagnostic
+        .related()
+        .map(|related| related.map(diagnostic_to_json).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    json!({
+        "message": diagnostic.to_string(),
+        "code": diagnostic.code().map(|code| code.to_string()),
+        "help": diagnostic.help().map(|help| help.to_string()),
+        "labels": labels,
+        "related": related,
+    })
+}
+
+/// Print a failed package as a single line of JSON to stdout.
+pub fn print_error_json(package: &Package, error: &miette::Report) {
+    println!(
+        "{}",
+        json!({
+            "package": package.name(),
+            "error": diagnostic_to_json(&**error),
+        })
+    );
 }
 
 #[macro_export]
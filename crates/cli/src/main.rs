@@ -8,6 +8,7 @@ mod systems;
 
 use app::{Commands, CLI};
 use clap::Parser;
+use commands::SyncCommands;
 use mimalloc::MiMalloc;
 use starbase::tracing::TracingOptions;
 use starbase::{App, MainResult};
@@ -39,22 +40,42 @@ async fn main() -> MainResult {
     app.set_state(cli.global_args());
     app.set_state(cli.clone());
     app.startup(systems::set_paths);
-    app.startup(systems::find_workspace);
     app.startup(systems::load_store);
+    app.startup(systems::find_workspace);
 
     match cli.command {
+        Commands::Audit(args) => {
+            app.execute_with_args(commands::audit, args);
+        }
         Commands::Build(args) => {
             app.execute_with_args(commands::build, args);
         }
+        Commands::Check(args) => {
+            app.execute_with_args(commands::check, args);
+        }
         Commands::Debug => {
             app.execute(commands::debug);
         }
         Commands::Init(args) => {
             app.execute_with_args(commands::init, args);
         }
+        Commands::Licenses(args) => {
+            app.execute_with_args(commands::licenses, args);
+        }
         Commands::New(args) => {
             app.execute_with_args(commands::new, args);
         }
+        Commands::Sbom(args) => {
+            app.execute_with_args(commands::sbom, args);
+        }
+        Commands::Sync(args) => match args.command {
+            SyncCommands::TsConfig(args) => {
+                app.execute_with_args(commands::sync_tsconfig, args);
+            }
+        },
+        Commands::Vendor(args) => {
+            app.execute_with_args(commands::vendor, args);
+        }
     };
 
     app.run().await?;
@@ -1,5 +1,6 @@
 use crate::app::{Commands, CLI};
 use crate::states::WorkingDir;
+use espresso_store::Store;
 use espresso_workspace::Workspace;
 use starbase::system;
 
@@ -10,6 +11,14 @@ pub fn find_workspace(
     resources: ResourcesMut,
 ) -> SystemResult {
     if !matches!(cli.command, Commands::Init(_) | Commands::New(_)) {
-        resources.set(Workspace::load_from(working_dir)?);
+        let workspace = Workspace::load_from(working_dir)?;
+
+        // Vendored dependencies take precedence over the store
+        if let Some(vendor_dir) = &workspace.manifest.get_install_settings().vendor_dir {
+            resources.get_mut::<Store>().vendor_dir =
+                Some(vendor_dir.to_logical_path(&workspace.root));
+        }
+
+        resources.set(workspace);
     }
 }
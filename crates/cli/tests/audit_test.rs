@@ -0,0 +1,105 @@
+mod utils;
+
+use espresso_lockfile::LOCKFILE_NAME;
+use espresso_manifest::MANIFEST_NAME;
+use starbase_sandbox::predicates::prelude::*;
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use utils::*;
+
+fn create_audit_sandbox() -> Sandbox {
+    let sandbox = create_empty_sandbox();
+    sandbox.create_file(MANIFEST_NAME, "[package]\nname = \"ns/app\"");
+    sandbox.create_file(
+        LOCKFILE_NAME,
+        r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+
+[[package]]
+name = "ns/pkg-b"
+version = "2.0.0"
+"#,
+    );
+    sandbox.create_file(
+        "advisories/ESPA-0001.toml",
+        r#"
+package = "ns/pkg-a"
+affected = "<1.2.0"
+severity = "medium"
+description = "Prototype pollution."
+"#,
+    );
+    sandbox
+}
+
+mod audit {
+    use super::*;
+
+    #[test]
+    fn errors_if_no_database() {
+        let sandbox = create_audit_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["audit", "--db", "unknown"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("No advisory database found"));
+    }
+
+    #[test]
+    fn fails_when_vulnerable() {
+        let sandbox = create_audit_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["audit", "--db", "advisories"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains("Prototype pollution."))
+            .stderr(predicate::str::contains(
+                "Found 1 vulnerable package(s) with a severity of low or higher.",
+            ));
+    }
+
+    #[test]
+    fn passes_when_below_severity() {
+        let sandbox = create_audit_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["audit", "--db", "advisories", "--severity", "high"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("ESPA-0001"));
+    }
+
+    #[test]
+    fn passes_when_not_affected() {
+        let sandbox = create_audit_sandbox();
+        sandbox.create_file(
+            LOCKFILE_NAME,
+            r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.2.0"
+"#,
+        );
+
+        create_espm_command(sandbox.path())
+            .args(["audit", "--db", "advisories"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("No vulnerabilities found"));
+    }
+
+    #[test]
+    fn can_output_json() {
+        let sandbox = create_audit_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["audit", "--db", "advisories", "--json"])
+            .assert()
+            .failure()
+            .stdout(predicate::str::contains(r#""id": "ESPA-0001""#))
+            .stdout(predicate::str::contains(r#""vulnerable": 1"#));
+    }
+}
@@ -1,6 +1,7 @@
 mod utils;
 
-use starbase_sandbox::create_sandbox;
+use starbase_sandbox::{create_sandbox, predicates::prelude::*};
+use std::time::Duration;
 use utils::create_espm_command;
 
 mod build {
@@ -32,6 +33,65 @@ mod build {
         assert!(sandbox.path().join("packages/foo/.espm/es2016").exists());
     }
 
+    #[test]
+    fn bails_on_failure_in_monorepo() {
+        let sandbox = create_sandbox("monorepo");
+        sandbox.create_file("packages/bar/src/cjs.mts", "export const a = require('a');");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--workspace", "--concurrency", "1"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Failed to transform module"));
+    }
+
+    #[test]
+    fn continues_past_failures_with_no_bail() {
+        let sandbox = create_sandbox("monorepo");
+        sandbox.create_file("packages/bar/src/cjs.mts", "export const a = require('a');");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--workspace", "--no-bail"])
+            .assert()
+            .failure();
+
+        assert!(sandbox.path().join("packages/baz/.espm/es2018").exists());
+        assert!(sandbox.path().join("packages/foo/.espm/es2018").exists());
+    }
+
+    #[test]
+    fn skips_dependents_of_failed_packages() {
+        let sandbox = create_sandbox("monorepo");
+        sandbox.create_file("packages/bar/src/cjs.mts", "export const a = require('a');");
+        sandbox.create_file(
+            "packages/foo/esp.toml",
+            "[package]\nname = \"mono/foo\"\n\n[dependencies]\n\"mono/bar\" = \"*\"\n",
+        );
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--workspace", "--no-bail"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("Skipped, as a dependency failed"));
+
+        assert!(sandbox.path().join("packages/baz/.espm/es2018").exists());
+        assert!(!sandbox.path().join("packages/foo/.espm/es2018").exists());
+    }
+
+    #[test]
+    fn keeps_watching_when_initial_build_fails() {
+        let sandbox = create_sandbox("polyrepo");
+        sandbox.create_file("src/cjs.mts", "export const a = require('a');");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--watch"])
+            .timeout(Duration::from_secs(10))
+            .assert()
+            .interrupted()
+            .stderr(predicate::str::contains("Failed to transform module"))
+            .stdout(predicate::str::contains("Watching for changes"));
+    }
+
     #[test]
     fn builds_selected_in_monorepo() {
         let sandbox = create_sandbox("monorepo");
@@ -46,6 +106,85 @@ mod build {
         assert!(!sandbox.path().join("packages/foo/.espm/es2017").exists());
     }
 
+    #[test]
+    fn builds_multiple_targets() {
+        let sandbox = create_sandbox("polyrepo");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--target", "es2020", "--target", "es2015"])
+            .assert()
+            .success();
+
+        assert!(sandbox.path().join(".espm/es2015").exists());
+        assert!(sandbox.path().join(".espm/es2020").exists());
+        assert!(!sandbox.path().join(".espm/es2018").exists());
+    }
+
+    #[test]
+    fn builds_all_targets() {
+        let sandbox = create_sandbox("polyrepo");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--all-targets"])
+            .assert()
+            .success();
+
+        for target in [
+            "es2015", "es2016", "es2017", "es2018", "es2019", "es2020", "es2021", "es2022",
+            "es2023", "es2024",
+        ] {
+            assert!(sandbox.path().join(".espm").join(target).exists());
+        }
+    }
+
+    #[test]
+    fn errors_for_target_with_all_targets() {
+        let sandbox = create_sandbox("polyrepo");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--target", "es2020", "--all-targets"])
+            .assert()
+            .failure();
+    }
+
+    #[test]
+    fn builds_with_concurrency() {
+        let sandbox = create_sandbox("polyrepo");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--target", "es2019", "--concurrency", "1"])
+            .assert()
+            .success();
+
+        assert!(sandbox.path().join(".espm/es2019").exists());
+    }
+
+    #[test]
+    fn writes_build_report() {
+        let sandbox = create_sandbox("polyrepo");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--target", "es2018"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Brotli"));
+
+        assert!(sandbox.path().join(".espm/es2018/build.json").exists());
+    }
+
+    #[test]
+    fn streams_reports_as_json() {
+        let sandbox = create_sandbox("polyrepo");
+
+        create_espm_command(sandbox.path())
+            .args(["build", "--target", "es2015,es2020", "--json"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"target\":\"es2015\""))
+            .stdout(predicate::str::contains("\"target\":\"es2020\""))
+            .stdout(predicate::str::contains("Building").not());
+    }
+
     #[test]
     fn copies_info_files_for_each_package() {
         let sandbox = create_sandbox("monorepo");
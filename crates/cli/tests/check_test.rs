@@ -0,0 +1,22 @@
+mod utils;
+
+use starbase_sandbox::{create_sandbox, predicates::prelude::*};
+use std::time::Duration;
+use utils::create_espm_command;
+
+mod check {
+    use super::*;
+
+    #[test]
+    fn keeps_watching_when_initial_check_fails() {
+        let sandbox = create_sandbox("polyrepo");
+        sandbox.create_file("src/invalid.mts", "export const a: number = 'a';");
+
+        create_espm_command(sandbox.path())
+            .args(["check", "--watch", "--jobs", "1"])
+            .timeout(Duration::from_secs(10))
+            .assert()
+            .interrupted()
+            .stdout(predicate::str::contains("Watching for changes"));
+    }
+}
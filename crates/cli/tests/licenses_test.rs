@@ -0,0 +1,157 @@
+mod utils;
+
+use espresso_lockfile::LOCKFILE_NAME;
+use espresso_manifest::MANIFEST_NAME;
+use starbase_sandbox::predicates::prelude::*;
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use utils::*;
+
+fn create_licenses_sandbox(policy: &str) -> Sandbox {
+    let sandbox = create_empty_sandbox();
+    sandbox.create_file(
+        MANIFEST_NAME,
+        format!("[package]\nname = \"ns/app\"\nlicense = \"MIT\"\n\n[licenses]\n{policy}"),
+    );
+    sandbox.create_file(
+        LOCKFILE_NAME,
+        r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+
+[[package]]
+name = "ns/pkg-b"
+version = "2.0.0"
+"#,
+    );
+    sandbox.create_file(
+        ".espresso/packages/ns/pkg-a/v1.0.0/es2018/esp.toml",
+        "[package]\nname = \"ns/pkg-a\"\nversion = \"1.0.0\"\nlicense = \"MIT OR Apache-2.0\"",
+    );
+    sandbox.create_file(
+        ".espresso/packages/ns/pkg-b/v2.0.0/es2018/esp.toml",
+        "[package]\nname = \"ns/pkg-b\"\nversion = \"2.0.0\"\nlicense = \"GPL-3.0-only\"",
+    );
+    sandbox
+}
+
+mod licenses {
+    use super::*;
+
+    #[test]
+    fn lists_all_licenses() {
+        let sandbox = create_licenses_sandbox("");
+
+        create_espm_command(sandbox.path())
+            .args(["licenses"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("ns/app"))
+            .stdout(predicate::str::contains("MIT OR Apache-2.0"))
+            .stdout(predicate::str::contains("GPL-3.0-only"));
+    }
+
+    #[test]
+    fn fails_when_denied() {
+        let sandbox = create_licenses_sandbox("deny = [\"GPL-3.0\"]");
+
+        create_espm_command(sandbox.path())
+            .args(["licenses"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Found 1 package(s) with a license that is not allowed",
+            ));
+    }
+
+    #[test]
+    fn fails_when_not_allowed() {
+        let sandbox = create_licenses_sandbox("allow = [\"Apache-2.0\"]");
+
+        create_espm_command(sandbox.path())
+            .args(["licenses"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "Found 1 package(s) with a license that is not allowed",
+            ));
+    }
+
+    #[test]
+    fn passes_when_allowed() {
+        let sandbox = create_licenses_sandbox("allow = [\"MIT\", \"GPL-3.0\"]");
+
+        create_espm_command(sandbox.path())
+            .args(["licenses"])
+            .assert()
+            .success();
+    }
+
+    #[test]
+    fn warns_about_mismatched_license_file() {
+        let sandbox = create_licenses_sandbox("");
+        sandbox.create_file("LICENSE", "All rights reserved. Do not copy this software.");
+
+        create_espm_command(sandbox.path())
+            .args(["licenses"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains(
+                "Declared license does not match the contents of the license file",
+            ));
+    }
+
+    #[test]
+    fn warns_about_uninstalled_packages() {
+        let sandbox = create_licenses_sandbox("");
+        sandbox.create_file(
+            LOCKFILE_NAME,
+            r#"
+[[package]]
+name = "ns/pkg-c"
+version = "3.0.0"
+"#,
+        );
+
+        create_espm_command(sandbox.path())
+            .args(["licenses"])
+            .assert()
+            .success()
+            .stderr(predicate::str::contains("unable to determine its license"));
+    }
+
+    #[test]
+    fn reads_vendored_packages() {
+        let sandbox = create_licenses_sandbox("");
+        sandbox.create_file(
+            MANIFEST_NAME,
+            "[package]\nname = \"ns/app\"\nlicense = \"MIT\"\n\n[install]\nvendor-dir = \"vendor\"",
+        );
+        sandbox.create_file(
+            "vendor/ns/pkg-b/v2.0.0/es2018/esp.toml",
+            "[package]\nname = \"ns/pkg-b\"\nversion = \"2.0.0\"\nlicense = \"ISC\"",
+        );
+
+        create_espm_command(sandbox.path())
+            .args(["licenses"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("ISC"))
+            .stdout(predicate::str::contains("GPL-3.0-only").not());
+    }
+
+    #[test]
+    fn can_output_json() {
+        let sandbox = create_licenses_sandbox("");
+
+        let assert = create_espm_command(sandbox.path())
+            .args(["licenses", "--json"])
+            .assert()
+            .success();
+
+        let output = String::from_utf8(assert.get_output().stdout.clone()).unwrap();
+
+        assert!(output.contains("\"name\": \"ns/pkg-a\""));
+        assert!(output.contains("\"allowed\": true"));
+    }
+}
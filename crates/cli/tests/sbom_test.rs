@@ -0,0 +1,94 @@
+mod utils;
+
+use espresso_lockfile::LOCKFILE_NAME;
+use espresso_manifest::MANIFEST_NAME;
+use starbase_sandbox::predicates::prelude::*;
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use utils::*;
+
+fn create_sbom_sandbox() -> Sandbox {
+    let sandbox = create_empty_sandbox();
+    sandbox.create_file(
+        MANIFEST_NAME,
+        r#"
+[package]
+name = "ns/app"
+version = "1.0.0"
+license = "MIT"
+
+[dependencies]
+"ns/pkg-a" = "^1.0.0"
+"#,
+    );
+    sandbox.create_file(
+        LOCKFILE_NAME,
+        r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+checksum = "abc123"
+"#,
+    );
+    sandbox
+}
+
+mod sbom {
+    use super::*;
+
+    #[test]
+    fn outputs_cyclonedx_by_default() {
+        let sandbox = create_sbom_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["sbom"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"bomFormat\": \"CycloneDX\""))
+            .stdout(predicate::str::contains("\"bom-ref\": \"ns/app@1.0.0\""))
+            .stdout(predicate::str::contains("\"content\": \"abc123\""));
+    }
+
+    #[test]
+    fn outputs_spdx() {
+        let sandbox = create_sbom_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["sbom", "--format", "spdx-json"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"spdxVersion\": \"SPDX-2.3\""))
+            .stdout(predicate::str::contains("\"name\": \"ns/pkg-a\""));
+    }
+
+    #[test]
+    fn writes_to_file() {
+        let sandbox = create_sbom_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["sbom", "--output", "sbom.json"])
+            .assert()
+            .success();
+
+        assert!(read_file(sandbox.path().join("sbom.json")).contains("CycloneDX"));
+    }
+
+    #[test]
+    fn only_includes_selected_packages() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(MANIFEST_NAME, "[workspace]\npackages = [\"*\"]");
+        sandbox.create_file(
+            "app/esp.toml",
+            "[package]\nname = \"ns/app\"\n\n[dependencies]\n\"ns/lib\" = \"*\"",
+        );
+        sandbox.create_file("lib/esp.toml", "[package]\nname = \"ns/lib\"");
+        sandbox.create_file("other/esp.toml", "[package]\nname = \"ns/other\"");
+
+        create_espm_command(sandbox.path())
+            .args(["sbom", "--package", "ns/app"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("\"bom-ref\": \"ns/app\""))
+            .stdout(predicate::str::contains("\"bom-ref\": \"ns/lib\""))
+            .stdout(predicate::str::contains("ns/other").not());
+    }
+}
@@ -0,0 +1,64 @@
+mod utils;
+
+use starbase_sandbox::{create_sandbox, predicates::prelude::*};
+use utils::{create_espm_command, read_file};
+
+mod sync_tsconfig {
+    use super::*;
+
+    #[test]
+    fn writes_tsconfigs_in_monorepo() {
+        let sandbox = create_sandbox("monorepo");
+
+        create_espm_command(sandbox.path())
+            .args(["sync", "tsconfig"])
+            .assert()
+            .success();
+
+        assert!(read_file(sandbox.path().join("tsconfig.json")).starts_with("// Generated by espm"));
+        assert!(read_file(sandbox.path().join("packages/foo/tsconfig.json"))
+            .starts_with("// Generated by espm"));
+    }
+
+    #[test]
+    fn overwrites_generated_tsconfigs() {
+        let sandbox = create_sandbox("polyrepo");
+
+        for _ in 0..2 {
+            create_espm_command(sandbox.path())
+                .args(["sync", "tsconfig"])
+                .assert()
+                .success();
+        }
+    }
+
+    #[test]
+    fn doesnt_overwrite_handwritten_tsconfigs() {
+        let sandbox = create_sandbox("monorepo");
+        sandbox.create_file("tsconfig.json", "{}");
+
+        create_espm_command(sandbox.path())
+            .args(["sync", "tsconfig"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "compiler::tsconfig::not_generated",
+            ));
+
+        assert_eq!(read_file(sandbox.path().join("tsconfig.json")), "{}");
+        assert!(!sandbox.path().join("packages/foo/tsconfig.json").exists());
+    }
+
+    #[test]
+    fn overwrites_handwritten_tsconfigs_when_forced() {
+        let sandbox = create_sandbox("polyrepo");
+        sandbox.create_file("tsconfig.json", "{}");
+
+        create_espm_command(sandbox.path())
+            .args(["sync", "tsconfig", "--force"])
+            .assert()
+            .success();
+
+        assert!(read_file(sandbox.path().join("tsconfig.json")).starts_with("// Generated by espm"));
+    }
+}
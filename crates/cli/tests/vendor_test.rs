@@ -0,0 +1,135 @@
+mod utils;
+
+use espresso_lockfile::LOCKFILE_NAME;
+use espresso_manifest::MANIFEST_NAME;
+use starbase_sandbox::predicates::prelude::*;
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use utils::*;
+
+fn create_vendor_sandbox() -> Sandbox {
+    let sandbox = create_empty_sandbox();
+    sandbox.create_file(
+        MANIFEST_NAME,
+        "# Root package\n[package]\nname = \"ns/app\"\n\n[install]\ntarget = \"es2020\"\n",
+    );
+    sandbox.create_file(
+        LOCKFILE_NAME,
+        r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+"#,
+    );
+    sandbox.create_file(
+        ".espresso/packages/ns/pkg-a/v1.0.0/es2020/esp.toml",
+        "[package]\nname = \"ns/pkg-a\"",
+    );
+    sandbox.create_file(
+        ".espresso/packages/ns/pkg-a/v1.0.0/es2020/index.mjs",
+        "export default 1;",
+    );
+    sandbox
+}
+
+mod vendor {
+    use super::*;
+
+    #[test]
+    fn copies_packages_from_store() {
+        let sandbox = create_vendor_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["vendor"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("Vendored 1 packages"));
+
+        let dir = sandbox.path().join("vendor/ns/pkg-a/v1.0.0/es2020");
+
+        assert!(dir.join("esp.toml").exists());
+        assert_eq!(read_file(dir.join("index.mjs")), "export default 1;");
+    }
+
+    #[test]
+    fn supports_custom_dir() {
+        let sandbox = create_vendor_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["vendor", "third-party/espm"])
+            .assert()
+            .success();
+
+        assert!(sandbox
+            .path()
+            .join("third-party/espm/ns/pkg-a/v1.0.0/es2020/index.mjs")
+            .exists());
+    }
+
+    #[test]
+    fn writes_vendor_dir_to_manifest() {
+        let sandbox = create_vendor_sandbox();
+
+        create_espm_command(sandbox.path())
+            .args(["vendor", "deps"])
+            .assert()
+            .success();
+
+        let manifest = read_file(sandbox.path().join(MANIFEST_NAME));
+
+        assert!(manifest.contains("# Root package"));
+        assert!(manifest.contains("target = \"es2020\""));
+        assert!(manifest.contains("vendor-dir = \"deps\""));
+    }
+
+    #[test]
+    fn removes_packages_that_are_no_longer_locked() {
+        let sandbox = create_vendor_sandbox();
+        sandbox.create_file(
+            "vendor/ns/pkg-a/v0.9.0/es2020/index.mjs",
+            "export default 0;",
+        );
+        sandbox.create_file(
+            "vendor/ns/pkg-a/v1.0.0/es2018/index.mjs",
+            "export default 1;",
+        );
+        sandbox.create_file(
+            "vendor/ns/pkg-old/v1.0.0/es2020/index.mjs",
+            "export default 2;",
+        );
+        sandbox.create_file("vendor/README.md", "Vendored dependencies");
+
+        create_espm_command(sandbox.path())
+            .args(["vendor"])
+            .assert()
+            .success();
+
+        let dir = sandbox.path().join("vendor");
+
+        assert!(dir.join("ns/pkg-a/v1.0.0/es2020/index.mjs").exists());
+        assert!(dir.join("README.md").exists());
+        assert!(!dir.join("ns/pkg-a/v0.9.0").exists());
+        assert!(!dir.join("ns/pkg-a/v1.0.0/es2018").exists());
+        assert!(!dir.join("ns/pkg-old").exists());
+    }
+
+    #[test]
+    fn errors_if_not_installed() {
+        let sandbox = create_vendor_sandbox();
+        sandbox.create_file(
+            LOCKFILE_NAME,
+            r#"
+[[package]]
+name = "ns/pkg-b"
+version = "2.0.0"
+"#,
+        );
+
+        create_espm_command(sandbox.path())
+            .args(["vendor"])
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains(
+                "has not been installed into the store",
+            ));
+    }
+}
@@ -4,11 +4,13 @@ mod category;
 mod channel;
 mod license_type;
 mod package_name;
+mod related_error;
 mod target;
 
 pub use category::*;
 pub use channel::*;
 pub use license_type::*;
 pub use package_name::*;
+pub use related_error::*;
 pub use semver::{Version, VersionReq};
 pub use target::*;
@@ -1,9 +1,38 @@
 use schematic::{SchemaType, Schematic};
 use serde::{Deserialize, Serialize};
-use spdx::Expression;
+use spdx::{Expression, LicenseItem};
+use std::collections::HashSet;
 use std::fmt::Display;
 use std::ops::Deref;
 
+pub use spdx::Licensee;
+
+// Minimum similarity for a license file to be considered a match.
+const TEXT_SIMILARITY_THRESHOLD: f32 = 0.75;
+
+// Compare texts using word bigrams, so that formatting, copyright
+// holders, and years have little effect on the result.
+fn tokenize(text: &str) -> HashSet<(String, String)> {
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_lowercase())
+        .collect::<Vec<_>>();
+
+    words
+        .windows(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect()
+}
+
+fn similarity(a: &HashSet<(String, String)>, b: &HashSet<(String, String)>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    (2 * a.intersection(b).count()) as f32 / (a.len() + b.len()) as f32
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 #[serde(try_from = "String", into = "String")]
 pub struct LicenseType(Expression);
@@ -18,6 +47,29 @@ impl LicenseType {
     pub fn parse(value: &str) -> Result<Self, spdx::ParseError> {
         Ok(Self(Expression::parse(value)?))
     }
+
+    /// Evaluate the expression (including `AND`, `OR`, and `WITH` exceptions)
+    /// against a policy. A license requirement is accepted when it satisfies an
+    /// allowed licensee (or no allow list exists), and no denied licensee.
+    pub fn is_allowed(&self, allow: &[Licensee], deny: &[Licensee]) -> bool {
+        self.0.evaluate(|req| {
+            (allow.is_empty() || allow.iter().any(|licensee| licensee.satisfies(req)))
+                && !deny.iter().any(|licensee| licensee.satisfies(req))
+        })
+    }
+
+    /// Return true if the provided license file content resembles the text
+    /// of at least one license within the expression.
+    pub fn matches_text(&self, content: &str) -> bool {
+        let content = tokenize(content);
+
+        self.0.requirements().any(|expr| match &expr.req.license {
+            LicenseItem::Spdx { id, .. } => {
+                similarity(&content, &tokenize(id.text())) >= TEXT_SIMILARITY_THRESHOLD
+            }
+            LicenseItem::Other { .. } => true,
+        })
+    }
 }
 
 impl TryFrom<String> for LicenseType {
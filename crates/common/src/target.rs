@@ -15,5 +15,7 @@ derive_enum!(
         Es2020,
         Es2021,
         Es2022,
+        Es2023,
+        Es2024,
     }
 );
@@ -0,0 +1,169 @@
+use crate::helpers::{create_relative_path, has_extension};
+use espresso_tsconfig::TsConfigLoader;
+use relative_path::RelativePathBuf;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+const MODULE_EXTENSIONS: [&str; 6] = ["ts", "tsx", "mts", "js", "jsx", "mjs"];
+
+/// Import aliases derived from the `baseUrl` and `paths` compiler options
+/// in `tsconfig.espm.json`, which resolve to files within the source directory.
+#[derive(Debug, Default)]
+pub struct PathAliases {
+    pub base_url: Option<PathBuf>,
+    pub paths: Vec<(String, Vec<RelativePathBuf>)>,
+    pub paths_root: PathBuf,
+    pub src_dir: PathBuf,
+}
+
+impl PathAliases {
+    pub fn load(package_root: &Path, src_dir: &Path) -> miette::Result<PathAliases> {
+        let tsconfig_file = package_root.join("tsconfig.espm.json");
+        let mut aliases = PathAliases {
+            paths_root: package_root.to_path_buf(),
+            src_dir: src_dir.to_path_buf(),
+            ..PathAliases::default()
+        };
+
+        if !tsconfig_file.exists() {
+            return Ok(aliases);
+        }
+
+        // Options may be inherited from extended files, with their paths
+        // made relative from the package root when resolved
+        let tsconfig = TsConfigLoader::load(&tsconfig_file)?;
+
+        let Some(options) = tsconfig.config.compiler_options else {
+            return Ok(aliases);
+        };
+
+        if let Some(base_url) = options.base_url {
+            let base_url = base_url.to_logical_path(package_root);
+
+            // Paths are relative from the base URL when defined
+            aliases.paths_root = base_url.clone();
+            aliases.base_url = Some(base_url);
+        }
+
+        if let Some(paths) = options.paths {
+            aliases.paths = paths.into_iter().collect();
+        }
+
+        debug!(
+            tsconfig = ?tsconfig_file,
+            base_url = ?aliases.base_url,
+            paths = ?aliases.paths.iter().map(|(pattern, _)| pattern).collect::<Vec<_>>(),
+            "Loaded import aliases",
+        );
+
+        Ok(aliases)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.base_url.is_none() && self.paths.is_empty()
+    }
+
+    /// Resolve an aliased specifier, imported from a file within the provided
+    /// directory, to a relative `.mjs` specifier. Returns `None` if the specifier
+    /// is not an alias, or does not resolve to a file in the source directory.
+    pub fn resolve(&self, specifier: &str, from_dir: &Path) -> Option<String> {
+        if specifier.starts_with('.') || specifier.starts_with('/') || self.is_empty() {
+            return None;
+        }
+
+        let file = self.resolve_file(specifier)?;
+        let mut path = create_relative_path(from_dir, &file);
+        path.set_extension("mjs");
+
+        let path = path.to_string();
+
+        Some(if path.starts_with("..") {
+            path
+        } else {
+            format!("./{path}")
+        })
+    }
+
+    fn resolve_file(&self, specifier: &str) -> Option<PathBuf> {
+        let mut candidates = vec![];
+
+        if let Some((targets, wildcard)) = self.match_paths(specifier) {
+            for target in targets {
+                candidates.push(
+                    RelativePathBuf::from(target.as_str().replacen('*', wildcard, 1))
+                        .to_logical_path(&self.paths_root),
+                );
+            }
+        }
+
+        if let Some(base_url) = &self.base_url {
+            candidates.push(RelativePathBuf::from(specifier).to_logical_path(base_url));
+        }
+
+        candidates
+            .into_iter()
+            .filter_map(|candidate| probe_file(&candidate))
+            // Files outside of the source directory are not compiled
+            .find(|file| file.starts_with(&self.src_dir))
+    }
+
+    // Exact patterns take precedence, otherwise the wildcard
+    // pattern with the longest prefix wins, like TypeScript.
+    fn match_paths<'a>(&'a self, specifier: &'a str) -> Option<(&'a [RelativePathBuf], &'a str)> {
+        let mut matched = None;
+        let mut matched_prefix_len = 0;
+
+        for (pattern, targets) in &self.paths {
+            match pattern.split_once('*') {
+                None => {
+                    if pattern == specifier {
+                        return Some((targets, ""));
+                    }
+                }
+                Some((prefix, suffix)) => {
+                    if specifier.len() >= prefix.len() + suffix.len()
+                        && specifier.starts_with(prefix)
+                        && specifier.ends_with(suffix)
+                        && (matched.is_none() || prefix.len() > matched_prefix_len)
+                    {
+                        matched_prefix_len = prefix.len();
+                        matched = Some((
+                            targets.as_slice(),
+                            &specifier[prefix.len()..specifier.len() - suffix.len()],
+                        ));
+                    }
+                }
+            }
+        }
+
+        matched
+    }
+}
+
+fn probe_file(path: &Path) -> Option<PathBuf> {
+    if path.is_file() && has_extension(path, &MODULE_EXTENSIONS) {
+        return Some(path.to_path_buf());
+    }
+
+    // Append instead of replacing, as the file name may contain periods
+    for ext in MODULE_EXTENSIONS {
+        let mut file = path.to_path_buf().into_os_string();
+        file.push(format!(".{ext}"));
+
+        let file = PathBuf::from(file);
+
+        if file.is_file() {
+            return Some(file);
+        }
+    }
+
+    for ext in MODULE_EXTENSIONS {
+        let file = path.join(format!("index.{ext}"));
+
+        if file.is_file() {
+            return Some(file);
+        }
+    }
+
+    None
+}
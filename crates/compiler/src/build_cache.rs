@@ -0,0 +1,143 @@
+use crate::compiler_plugin::CompilerPlugins;
+use espresso_common::{EsTarget, Version};
+use espresso_manifest::ManifestBuild;
+use espresso_tsconfig::TsConfigLoader;
+use miette::IntoDiagnostic;
+use relative_path::RelativePathBuf;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use starbase_utils::{fs, glob, json};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Hashes of the inputs from the previous build of a target, which are
+/// used to determine which source files need to be compiled again.
+#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct BuildCache {
+    /// Source files (relative from `src`) mapped to a hash of their contents.
+    pub files: BTreeMap<RelativePathBuf, String>,
+
+    /// Hash of everything that affects the output of all files,
+    /// like build settings, the target, and compiler versions.
+    pub settings_hash: String,
+
+    /// Hash of the declarations of workspace dependencies, which
+    /// declarations are type checked against when generated.
+    pub dependencies_hash: String,
+
+    #[serde(skip)]
+    pub path: PathBuf,
+}
+
+impl BuildCache {
+    pub fn load(path: PathBuf) -> BuildCache {
+        if path.exists() {
+            debug!(cache = ?path, "Loading build cache");
+
+            match json::read_file::<_, BuildCache>(&path) {
+                Ok(cache) => {
+                    return BuildCache { path, ..cache };
+                }
+                Err(error) => {
+                    warn!(cache = ?path, "Failed to read build cache, rebuilding everything: {error}");
+                }
+            }
+        }
+
+        BuildCache {
+            path,
+            ..BuildCache::default()
+        }
+    }
+
+    pub fn save(&self) -> miette::Result<()> {
+        debug!(cache = ?self.path, "Saving build cache");
+
+        json::write_file(&self.path, self, true)?;
+
+        Ok(())
+    }
+
+    /// Return true if the file has not changed since it was last cached.
+    pub fn is_unchanged(&self, file: &RelativePathBuf, hash: &str) -> bool {
+        self.files.get(file).is_some_and(|cached| cached == hash)
+    }
+
+    /// Return true if exactly the provided files were cached, regardless of their contents.
+    pub fn has_files<'a>(&self, files: impl Iterator<Item = &'a RelativePathBuf>) -> bool {
+        let files = files.collect::<BTreeSet<_>>();
+
+        files.len() == self.files.len() && files.iter().all(|file| self.files.contains_key(*file))
+    }
+}
+
+pub fn hash_file(path: &Path) -> miette::Result<String> {
+    let contents = std::fs::read(path).into_diagnostic()?;
+
+    Ok(format!("{:x}", Sha256::digest(contents)))
+}
+
+pub fn hash_settings(
+    build_settings: &ManifestBuild,
+    target: &EsTarget,
+    package_root: &Path,
+    plugins: &CompilerPlugins,
+    ts_version: Option<&Version>,
+) -> miette::Result<String> {
+    let mut hasher = Sha256::new();
+
+    // Debug output is stable for the same values
+    hasher.update(format!("{build_settings:?}"));
+    hasher.update(target.to_string());
+    hasher.update(env!("CARGO_PKG_VERSION"));
+
+    if let Some(ts_version) = ts_version {
+        hasher.update(ts_version.to_string());
+    }
+
+    // Aliases and declarations are affected by a custom tsconfig,
+    // and any file that it extends
+    let tsconfig_file = package_root.join("tsconfig.espm.json");
+
+    if tsconfig_file.exists() {
+        for file in TsConfigLoader::load(&tsconfig_file)?.files {
+            hasher.update(fs::read_file(file)?);
+        }
+    }
+
+    // Plugins transform every module, and Wasm plugins
+    // may be updated without their path changing
+    for plugin in &plugins.native {
+        hasher.update(plugin.name());
+    }
+
+    for plugin in &plugins.wasm {
+        hasher.update(hash_file(Path::new(&plugin.0))?);
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hash the declarations within the output directories of workspace dependencies.
+/// Dependencies are built before their dependents, so these are up to date.
+pub fn hash_dependencies(out_dirs: &[PathBuf]) -> miette::Result<String> {
+    let mut hasher = Sha256::new();
+
+    for out_dir in out_dirs {
+        if !out_dir.exists() {
+            continue;
+        }
+
+        let mut files = glob::walk_files(out_dir, ["**/*.d.mts"])?;
+        files.sort();
+
+        for file in files {
+            hasher.update(file.strip_prefix(out_dir).unwrap().to_string_lossy().as_bytes());
+            hasher.update(hash_file(&file)?);
+        }
+    }
+
+    Ok(format!("{:x}", hasher.finalize()))
+}
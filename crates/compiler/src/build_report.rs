@@ -0,0 +1,161 @@
+use espresso_common::{EsTarget, PackageName};
+use espresso_manifest::ManifestBuild;
+use espresso_package::SourceFiles;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use miette::IntoDiagnostic;
+use relative_path::{RelativePath, RelativePathBuf};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use starbase_utils::{glob, json};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+pub const REPORT_NAME: &str = "build.json";
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReportFile {
+    /// Output file, relative from the output directory.
+    pub path: RelativePathBuf,
+
+    /// Source file this was compiled from, relative from `src`.
+    pub source: Option<RelativePathBuf>,
+
+    /// Size in bytes.
+    pub size: u64,
+
+    pub gzip_size: u64,
+
+    pub brotli_size: u64,
+
+    /// SHA-256 hash (hex) of the contents.
+    pub hash: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReportTimings {
+    /// Total duration of the build, in milliseconds.
+    pub total: u64,
+
+    /// Duration of TypeScript declaration generation, in milliseconds.
+    pub declarations: Option<u64>,
+}
+
+/// A machine-readable report of a build for a single target,
+/// which is written to `.espm/<target>/build.json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildReport {
+    pub package: PackageName,
+    pub target: EsTarget,
+    pub settings: ManifestBuild,
+    pub timings: BuildReportTimings,
+    pub files: Vec<BuildReportFile>,
+
+    #[serde(skip)]
+    pub out_dir: PathBuf,
+}
+
+impl BuildReport {
+    pub fn get_total_size(&self) -> (u64, u64, u64) {
+        self.files.iter().fold((0, 0, 0), |acc, file| {
+            (
+                acc.0 + file.size,
+                acc.1 + file.gzip_size,
+                acc.2 + file.brotli_size,
+            )
+        })
+    }
+
+    /// Gather all files within the output directory, and map them to their source.
+    pub fn load_files(&mut self, sources: &SourceFiles) -> miette::Result<()> {
+        debug!(out_dir = ?self.out_dir, "Gathering output files for build report");
+
+        let mut files = vec![];
+
+        for file in glob::walk_files(&self.out_dir, ["**/*"])? {
+            let rel_file = RelativePathBuf::from_path(file.strip_prefix(&self.out_dir).unwrap())
+                .into_diagnostic()?;
+
+            if rel_file.as_str() == REPORT_NAME {
+                continue;
+            }
+
+            files.push(create_file(&file, rel_file, sources)?);
+        }
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+
+        self.files = files;
+
+        Ok(())
+    }
+
+    pub fn save(&self) -> miette::Result<()> {
+        let path = self.out_dir.join(REPORT_NAME);
+
+        debug!(report = ?path, "Writing build report");
+
+        json::write_file(path, self, true)?;
+
+        Ok(())
+    }
+}
+
+fn create_file(
+    path: &Path,
+    rel_path: RelativePathBuf,
+    sources: &SourceFiles,
+) -> miette::Result<BuildReportFile> {
+    let contents = std::fs::read(path).into_diagnostic()?;
+
+    Ok(BuildReportFile {
+        source: find_source(&rel_path, sources),
+        path: rel_path,
+        size: contents.len() as u64,
+        gzip_size: gzip_size(&contents)?,
+        brotli_size: brotli_size(&contents)?,
+        hash: format!("{:x}", Sha256::digest(&contents)),
+    })
+}
+
+// Modules change extension when compiled, while assets are copied as-is.
+fn find_source(out_file: &RelativePath, sources: &SourceFiles) -> Option<RelativePathBuf> {
+    for suffix in [".d.mts.map", ".d.mts", ".mjs.map", ".mjs"] {
+        if let Some(stem) = out_file.as_str().strip_suffix(suffix) {
+            return sources
+                .modules
+                .iter()
+                .find(|module| module.with_extension("").as_str() == stem)
+                .cloned();
+        }
+    }
+
+    sources
+        .assets
+        .iter()
+        .find(|asset| asset.as_relative_path() == out_file)
+        .cloned()
+}
+
+fn gzip_size(contents: &[u8]) -> miette::Result<u64> {
+    let mut encoder = GzEncoder::new(vec![], Compression::best());
+    encoder.write_all(contents).into_diagnostic()?;
+
+    Ok(encoder.finish().into_diagnostic()?.len() as u64)
+}
+
+fn brotli_size(contents: &[u8]) -> miette::Result<u64> {
+    let mut output = vec![];
+
+    {
+        // Quality of 11 and window of 22, which is what servers commonly use
+        let mut writer = brotli::CompressorWriter::new(&mut output, 4096, 11, 22);
+        writer.write_all(contents).into_diagnostic()?;
+    }
+
+    Ok(output.len() as u64)
+}
@@ -1,24 +1,51 @@
+use crate::aliases::PathAliases;
 use crate::asset::Asset;
-use crate::declarations::Declarations;
-use crate::helpers::OUT_DIR;
+use crate::build_cache::{hash_dependencies, hash_file, hash_settings, BuildCache};
+use crate::build_report::{BuildReport, BuildReportTimings};
+use crate::compiler_error::CompilerError;
+use crate::compiler_plugin::{
+    load_wasm_plugin, resolve_wasm_plugin, CompilerPlugin, CompilerPlugins,
+};
+use crate::declarations::{Declarations, WorkspacePackages};
+use crate::helpers::{get_default_concurrency, has_extension, OUT_DIR};
 use crate::module::Module;
 use espresso_common::EsTarget;
 use espresso_manifest::ManifestBuild;
 use espresso_package::{Package, SourceFiles};
 use espresso_store::Store;
 use miette::IntoDiagnostic;
+use relative_path::{RelativePath, RelativePathBuf};
 use starbase_styles::color;
 use starbase_utils::fs;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Instant;
+use swc::config::PluginConfig;
 use swc::Compiler as SwcCompiler;
 use swc_core::common::{FilePathMapping, SourceMap};
+use tokio::sync::Semaphore;
 use tokio::task::{self, JoinHandle};
 use tracing::debug;
 
+fn is_module_file(file: &RelativePath) -> bool {
+    file.extension().is_some_and(|ext| {
+        ["js", "jsx", "mjs", "ts", "tsx", "mts"]
+            .iter()
+            .any(|e| ext.eq_ignore_ascii_case(e))
+    })
+}
+
 pub struct Compiler<'pkg> {
+    /// Maximum number of modules and assets to compile in parallel.
+    pub concurrency: usize,
+
+    /// All packages in the workspace, for referencing dependencies in declarations.
+    pub workspace_packages: Arc<WorkspacePackages>,
+
     compiler: Arc<SwcCompiler>,
     package: &'pkg Package,
+    plugins: Vec<Arc<dyn CompilerPlugin>>,
     store: Arc<Store>,
 }
 
@@ -27,17 +54,81 @@ impl<'pkg> Compiler<'pkg> {
         debug!(package = package.name(), "Creating compiler");
 
         Ok(Compiler {
+            concurrency: get_default_concurrency(),
+            workspace_packages: Arc::new(BTreeMap::new()),
             package,
             compiler: Arc::new(SwcCompiler::new(Arc::new(SourceMap::new(
                 FilePathMapping::empty(),
             )))),
+            plugins: vec![],
             store,
         })
     }
 
+    /// Register a plugin to transform every module with. Plugins
+    /// run in the order they are added, after `[[build.plugins]]`.
+    pub fn add_plugin(&mut self, plugin: impl CompilerPlugin + 'static) {
+        debug!(plugin = plugin.name(), "Registering compiler plugin");
+
+        self.plugins.push(Arc::new(plugin));
+    }
+
+    pub fn get_out_dir(&self, target: EsTarget) -> PathBuf {
+        self.package.root.join(OUT_DIR).join(target.to_string())
+    }
+
+    pub fn get_cache_file(&self, target: EsTarget) -> PathBuf {
+        self.package
+            .root
+            .join(OUT_DIR)
+            .join(format!("cache.{target}.json"))
+    }
+
+    /// Remove the build cache of each target, so that
+    /// all files are compiled again on the next compile.
+    pub fn clear_cache(&self, targets: &[EsTarget]) -> miette::Result<()> {
+        for target in targets {
+            fs::remove_file(self.get_cache_file(*target))?;
+        }
+
+        Ok(())
+    }
+
     pub async fn compile(&self, target: EsTarget) -> miette::Result<PathBuf> {
-        let out_dir = self.package.root.join(OUT_DIR).join(target.to_string());
         let sources = self.package.load_source_files()?;
+        let report = self.compile_target(target, &sources, None).await?;
+
+        Ok(report.out_dir)
+    }
+
+    /// Compile multiple targets, each into their own output directory. Declarations
+    /// are not affected by the target, so tsc is only ran for the first target, and
+    /// its declarations are copied to the others. Targets should be sorted from oldest
+    /// to newest, so that tsc type checks against the smallest `lib`.
+    pub async fn compile_targets(&self, targets: &[EsTarget]) -> miette::Result<Vec<BuildReport>> {
+        let sources = self.package.load_source_files()?;
+        let mut reports: Vec<BuildReport> = vec![];
+
+        for target in targets {
+            let declarations_dir = reports.first().map(|report| report.out_dir.clone());
+
+            reports.push(
+                self.compile_target(*target, &sources, declarations_dir)
+                    .await?,
+            );
+        }
+
+        Ok(reports)
+    }
+
+    async fn compile_target(
+        &self,
+        target: EsTarget,
+        sources: &SourceFiles,
+        declarations_dir: Option<PathBuf>,
+    ) -> miette::Result<BuildReport> {
+        let started = Instant::now();
+        let out_dir = self.get_out_dir(target);
 
         debug!(
             out_dir = ?out_dir,
@@ -47,53 +138,180 @@ impl<'pkg> Compiler<'pkg> {
         );
 
         let build_settings = Arc::new(self.package.manifest.build.clone());
-        let assets = self.create_assets(&sources, &out_dir, Arc::clone(&build_settings));
-        let modules = self.create_modules(&sources, &out_dir, Arc::clone(&build_settings));
+        let plugins = Arc::new(self.create_plugins(&build_settings)?);
+        let settings_hash = self
+            .create_settings_hash(target, sources, &out_dir, &build_settings, &plugins)
+            .await?;
+        let mut cache = BuildCache::load(self.get_cache_file(target));
 
-        // Delete previous build
-        fs::remove_dir_all(&out_dir)?;
+        // Settings affect the output of every file, so rebuild everything
+        if cache.settings_hash != settings_hash || !out_dir.exists() {
+            debug!(out_dir = ?out_dir, "Build settings have changed, rebuilding all files");
 
-        let mut futures: Vec<JoinHandle<miette::Result<()>>> = vec![];
-        let compiler = self.compiler.clone();
+            fs::remove_dir_all(&out_dir)?;
+
+            cache.files.clear();
+            cache.settings_hash = settings_hash;
+        }
+
+        let dependencies_hash =
+            self.create_dependencies_hash(target, sources, &out_dir, &build_settings)?;
+        let dependencies_changed = cache.dependencies_hash != dependencies_hash;
 
-        // Copy assets
-        futures.push(task::spawn(async {
-            for asset in assets {
-                asset.copy()?;
+        // Only compile files that have changed since the previous build
+        let mut hashes = BTreeMap::new();
+        let mut changed = SourceFiles::default();
+
+        for (list, changed_list) in [
+            (&sources.assets, &mut changed.assets),
+            (&sources.modules, &mut changed.modules),
+        ] {
+            for file in list {
+                let hash = hash_file(&file.to_path(&self.package.src_dir))?;
+
+                if !cache.is_unchanged(file, &hash) {
+                    changed_list.push(file.clone());
+                }
+
+                hashes.insert(file.clone(), hash);
             }
+        }
+
+        // Imports are resolved against the files that exist (through aliases and
+        // extensionless paths), so importers must be compiled again when files are
+        // added or removed, even if their contents haven't changed
+        if !cache.files.is_empty() && !cache.has_files(hashes.keys()) {
+            debug!("Source files have been added or removed, recompiling all modules");
 
-            Ok(())
-        }));
+            changed.modules = sources.modules.clone();
+        }
+
+        // Remove the output of files that no longer exist
+        let mut removed_modules = false;
+
+        for file in cache.files.keys() {
+            if !hashes.contains_key(file) {
+                removed_modules = removed_modules || is_module_file(file);
 
-        // Transform modules
-        futures.push(task::spawn(async move {
-            for module in modules {
-                module.transform(&compiler, &target).await?;
+                self.remove_output(file, &out_dir)?;
             }
+        }
 
-            Ok(())
-        }));
+        debug!(
+            assets = changed.assets.len(),
+            modules = changed.modules.len(),
+            unchanged = hashes.len() - changed.assets.len() - changed.modules.len(),
+            "Determined changed source files",
+        );
 
-        // Generate TypeScript declarations
-        if sources.typescript {
-            let declarations = Declarations::new(
-                self.package.root.clone(),
-                out_dir.clone(),
-                Arc::clone(&build_settings),
-                Arc::clone(&self.store),
-            );
+        let assets = self.create_assets(&changed, &out_dir, Arc::clone(&build_settings));
+        let aliases = Arc::new(PathAliases::load(
+            &self.package.root,
+            &self.package.src_dir,
+        )?);
+        let modules = self.create_modules(
+            &changed,
+            &out_dir,
+            Arc::clone(&build_settings),
+            aliases,
+            plugins,
+        );
+
+        let mut futures: Vec<JoinHandle<miette::Result<()>>> = vec![];
+        let mut declarations_future: Option<JoinHandle<miette::Result<u64>>> = None;
+
+        // Generate TypeScript declarations first, as tsc runs in a separate process
+        // for the duration of the build. Types may be inferred across files,
+        // so all declarations are generated again when any module changes, or when
+        // the declarations of a workspace dependency have changed.
+        if sources.typescript
+            && (!changed.modules.is_empty() || removed_modules || dependencies_changed)
+        {
+            let declarations = self.create_declarations(&out_dir, Arc::clone(&build_settings));
+
+            declarations_future = Some(task::spawn(async move {
+                let started = Instant::now();
+
+                match declarations_dir {
+                    Some(dir) => declarations.copy_from(&dir)?,
+                    None => declarations.generate(&target).await?,
+                };
+
+                Ok(started.elapsed().as_millis() as u64)
+            }));
+        } else if sources.typescript {
+            debug!("No modules have changed, skipping TypeScript declarations");
+        }
+
+        // Copy assets and transform modules across a bounded pool of blocking
+        // threads, as both are CPU bound and would otherwise stall the runtime
+        let semaphore = Arc::new(Semaphore::new(self.concurrency.max(1)));
+
+        debug!(
+            concurrency = self.concurrency,
+            "Copying {} assets and transforming {} modules",
+            assets.len(),
+            modules.len(),
+        );
+
+        for asset in assets {
+            let permit = Arc::clone(&semaphore)
+                .acquire_owned()
+                .await
+                .into_diagnostic()?;
+
+            futures.push(task::spawn_blocking(move || {
+                let _permit = permit;
+
+                asset.copy()
+            }));
+        }
+
+        for module in modules {
+            let compiler = Arc::clone(&self.compiler);
+            let permit = Arc::clone(&semaphore)
+                .acquire_owned()
+                .await
+                .into_diagnostic()?;
 
-            futures.push(task::spawn(async move {
-                declarations.generate(&target).await?;
+            futures.push(task::spawn_blocking(move || {
+                let _permit = permit;
 
-                Ok(())
+                module.transform(&compiler, &target)
             }));
         }
 
+        // Wait for everything to finish, so that all failures are reported
+        let mut errors = vec![];
+        let mut declarations_duration = None;
+
         for future in futures {
-            future.await.into_diagnostic()??;
+            if let Err(error) = future.await.into_diagnostic()? {
+                errors.push(error);
+            }
+        }
+
+        if let Some(future) = declarations_future {
+            match future.await.into_diagnostic()? {
+                Ok(duration) => {
+                    declarations_duration = Some(duration);
+                }
+                Err(error) => {
+                    errors.push(error);
+                }
+            }
         }
 
+        if !errors.is_empty() {
+            return Err(CompilerError::from_reports(errors));
+        }
+
+        cache.files = hashes;
+        cache.dependencies_hash = dependencies_hash;
+        cache.save()?;
+
+        self.package.copy_info_files(&out_dir)?;
+
         debug!(
             out_dir = ?out_dir,
             target = target.to_string(),
@@ -101,7 +319,132 @@ impl<'pkg> Compiler<'pkg> {
             color::id(self.package.name()),
         );
 
-        Ok(out_dir)
+        let mut report = BuildReport {
+            package: self.package.manifest.package.name.clone(),
+            target,
+            settings: (*build_settings).clone(),
+            timings: BuildReportTimings {
+                total: started.elapsed().as_millis() as u64,
+                declarations: declarations_duration,
+            },
+            files: vec![],
+            out_dir,
+        };
+
+        report.load_files(sources)?;
+        report.save()?;
+
+        Ok(report)
+    }
+
+    /// Recompile only the provided source files (absolute paths), which have changed
+    /// since the previous compile. Files that no longer exist will have their output
+    /// removed, and declarations are regenerated when a TypeScript file changed.
+    pub async fn compile_files(
+        &self,
+        target: EsTarget,
+        files: &[PathBuf],
+    ) -> miette::Result<BuildReport> {
+        let started = Instant::now();
+        let out_dir = self.get_out_dir(target);
+        let sources = self.package.load_source_files()?;
+        let build_settings = Arc::new(self.package.manifest.build.clone());
+        let plugins = Arc::new(self.create_plugins(&build_settings)?);
+        let settings_hash = self
+            .create_settings_hash(target, &sources, &out_dir, &build_settings, &plugins)
+            .await?;
+        let mut cache = BuildCache::load(self.get_cache_file(target));
+
+        // Settings affect the output of every file, and files that have been added or
+        // removed may change how other modules resolve their imports, so compile
+        // everything that's affected
+        if cache.settings_hash != settings_hash
+            || !cache.has_files(sources.assets.iter().chain(&sources.modules))
+        {
+            return self.compile_target(target, &sources, None).await;
+        }
+
+        debug!(
+            out_dir = ?out_dir,
+            target = target.to_string(),
+            files = ?files,
+            "Recompiling changed files for package {}",
+            color::id(self.package.name()),
+        );
+
+        let aliases = Arc::new(PathAliases::load(
+            &self.package.root,
+            &self.package.src_dir,
+        )?);
+        let dependencies_hash =
+            self.create_dependencies_hash(target, &sources, &out_dir, &build_settings)?;
+        let mut typescript_changed = cache.dependencies_hash != dependencies_hash;
+
+        for file in files {
+            let Ok(rel_file) = file.strip_prefix(&self.package.src_dir) else {
+                continue;
+            };
+            let rel_file = RelativePathBuf::from_path(rel_file).into_diagnostic()?;
+
+            if has_extension(file, &["ts", "tsx", "mts"]) {
+                typescript_changed = true;
+            }
+
+            if sources.modules.contains(&rel_file) {
+                self.create_module(
+                    &rel_file,
+                    &out_dir,
+                    Arc::clone(&build_settings),
+                    Arc::clone(&aliases),
+                    Arc::clone(&plugins),
+                )
+                .transform(&self.compiler, &target)?;
+            } else if sources.assets.contains(&rel_file) {
+                self.create_asset(&rel_file, &out_dir, Arc::clone(&build_settings))
+                    .copy()?;
+            } else {
+                if !file.exists() {
+                    self.remove_output(&rel_file, &out_dir)?;
+                }
+
+                continue;
+            }
+
+            cache.files.insert(rel_file, hash_file(file)?);
+        }
+
+        let mut declarations_duration = None;
+
+        if sources.typescript && typescript_changed {
+            let declarations_started = Instant::now();
+
+            self.create_declarations(&out_dir, Arc::clone(&build_settings))
+                .generate(&target)
+                .await?;
+
+            declarations_duration = Some(declarations_started.elapsed().as_millis() as u64);
+        }
+
+        cache.dependencies_hash = dependencies_hash;
+        cache.save()?;
+
+        // Keep the report in sync with the output directory
+        let mut report = BuildReport {
+            package: self.package.manifest.package.name.clone(),
+            target,
+            settings: (*build_settings).clone(),
+            timings: BuildReportTimings {
+                total: started.elapsed().as_millis() as u64,
+                declarations: declarations_duration,
+            },
+            files: vec![],
+            out_dir,
+        };
+
+        report.load_files(&sources)?;
+        report.save()?;
+
+        Ok(report)
     }
 
     pub fn create_assets(
@@ -113,13 +456,7 @@ impl<'pkg> Compiler<'pkg> {
         sources
             .assets
             .iter()
-            .map(|asset_path| {
-                Asset::new(
-                    asset_path.to_path(&self.package.src_dir),
-                    asset_path.to_path(out_dir),
-                    Arc::clone(&build_settings),
-                )
-            })
+            .map(|asset_path| self.create_asset(asset_path, out_dir, Arc::clone(&build_settings)))
             .collect::<Vec<_>>()
     }
 
@@ -128,21 +465,202 @@ impl<'pkg> Compiler<'pkg> {
         sources: &SourceFiles,
         out_dir: &Path,
         build_settings: Arc<ManifestBuild>,
+        aliases: Arc<PathAliases>,
+        plugins: Arc<CompilerPlugins>,
     ) -> Vec<Module> {
         sources
             .modules
             .iter()
             .map(|module_path| {
-                // Always output as .mjs since we're ESM only
-                let mut out_file = module_path.to_path(out_dir);
-                out_file.set_extension("mjs");
-
-                Module::new(
-                    module_path.to_path(&self.package.src_dir),
-                    out_file,
+                self.create_module(
+                    module_path,
+                    out_dir,
                     Arc::clone(&build_settings),
+                    Arc::clone(&aliases),
+                    Arc::clone(&plugins),
                 )
             })
             .collect::<Vec<_>>()
     }
+
+    async fn create_settings_hash(
+        &self,
+        target: EsTarget,
+        sources: &SourceFiles,
+        out_dir: &Path,
+        build_settings: &Arc<ManifestBuild>,
+        plugins: &CompilerPlugins,
+    ) -> miette::Result<String> {
+        // A version requirement may resolve to a newer TypeScript release,
+        // but is only resolved when there are declarations to generate
+        let ts_version = if sources.typescript {
+            Some(
+                self.create_declarations(out_dir, Arc::clone(build_settings))
+                    .resolve_typescript_version()
+                    .await?,
+            )
+        } else {
+            None
+        };
+
+        hash_settings(
+            build_settings,
+            &target,
+            &self.package.root,
+            plugins,
+            ts_version.as_ref(),
+        )
+    }
+
+    // Declarations of dependents are type checked against their workspace
+    // dependencies, so they must be generated again when those change
+    fn create_dependencies_hash(
+        &self,
+        target: EsTarget,
+        sources: &SourceFiles,
+        out_dir: &Path,
+        build_settings: &Arc<ManifestBuild>,
+    ) -> miette::Result<String> {
+        if !sources.typescript {
+            return Ok(String::new());
+        }
+
+        let declarations = self.create_declarations(out_dir, Arc::clone(build_settings));
+        let mut out_dirs = declarations
+            .get_referenced_packages()
+            .into_iter()
+            .map(|(_, package)| package.root.join(OUT_DIR).join(target.to_string()))
+            .collect::<Vec<_>>();
+
+        if out_dirs.is_empty() {
+            return Ok(String::new());
+        }
+
+        out_dirs.sort();
+
+        hash_dependencies(&out_dirs)
+    }
+
+    fn create_declarations(
+        &self,
+        out_dir: &Path,
+        build_settings: Arc<ManifestBuild>,
+    ) -> Declarations {
+        let mut declarations = Declarations::new(
+            self.package.root.clone(),
+            out_dir.to_path_buf(),
+            build_settings,
+            Arc::clone(&self.store),
+        );
+
+        if let Some(package) = self
+            .workspace_packages
+            .get(&self.package.manifest.package.name)
+        {
+            declarations.dependencies = package.dependencies.clone();
+            declarations.workspace_packages = Arc::clone(&self.workspace_packages);
+        }
+
+        declarations
+    }
+
+    fn create_asset(
+        &self,
+        asset_path: &RelativePath,
+        out_dir: &Path,
+        build_settings: Arc<ManifestBuild>,
+    ) -> Asset {
+        Asset::new(
+            asset_path.to_path(&self.package.src_dir),
+            asset_path.to_path(out_dir),
+            build_settings,
+        )
+    }
+
+    fn create_module(
+        &self,
+        module_path: &RelativePath,
+        out_dir: &Path,
+        build_settings: Arc<ManifestBuild>,
+        aliases: Arc<PathAliases>,
+        plugins: Arc<CompilerPlugins>,
+    ) -> Module {
+        // Always output as .mjs since we're ESM only
+        let mut out_file = module_path.to_path(out_dir);
+        out_file.set_extension("mjs");
+
+        let mut module = Module::new(
+            module_path.to_path(&self.package.src_dir),
+            out_file,
+            build_settings,
+            aliases,
+        );
+        module.plugins = plugins;
+        module
+    }
+
+    // Wasm plugins from the manifest run first, as they are ran by swc
+    // as part of its transforms, followed by registered plugins
+    fn create_plugins(&self, build_settings: &ManifestBuild) -> miette::Result<CompilerPlugins> {
+        let mut wasm = vec![];
+        let wasm_cache_dir = self.store.cache_dir.join("swc-plugins");
+
+        for plugin in &build_settings.plugins {
+            let path = match (&plugin.name, &plugin.path) {
+                (_, Some(path)) => Some(path.to_logical_path(&self.package.root)),
+                (Some(name), None) => resolve_wasm_plugin(name, &self.package.root),
+                (None, None) => None,
+            };
+
+            let Some(path) = path.filter(|path| path.is_file()) else {
+                return Err(CompilerError::PluginNotFound {
+                    label: plugin.get_label(),
+                }
+                .into());
+            };
+
+            debug!(plugin = plugin.get_label(), path = ?path, "Loading swc plugin");
+
+            load_wasm_plugin(&path, &Some(wasm_cache_dir.to_string_lossy().to_string()))?;
+
+            wasm.push(PluginConfig(
+                path.to_string_lossy().to_string(),
+                if plugin.config.is_null() {
+                    serde_json::json!({})
+                } else {
+                    plugin.config.clone()
+                },
+            ));
+        }
+
+        Ok(CompilerPlugins {
+            native: self.plugins.clone(),
+            wasm,
+            wasm_cache_dir: Some(wasm_cache_dir),
+        })
+    }
+
+    // Deleted files can't be classified, so infer the outputs from the extension
+    fn remove_output(&self, rel_file: &RelativePath, out_dir: &Path) -> miette::Result<()> {
+        let out_file = rel_file.to_path(out_dir);
+        let mut files = vec![];
+
+        if is_module_file(rel_file) {
+            for ext in ["mjs", "mjs.map", "d.mts", "d.mts.map"] {
+                files.push(out_file.with_extension(ext));
+            }
+        } else {
+            files.push(out_file);
+        }
+
+        for file in files {
+            if file.exists() {
+                debug!(file = ?file, "Removing output of deleted source file");
+
+                fs::remove_file(file)?;
+            }
+        }
+
+        Ok(())
+    }
 }
@@ -1,3 +1,5 @@
+use crate::tsc_diagnostics::TscFileDiagnostics;
+use espresso_common::{RelatedError, Version};
 use miette::Diagnostic;
 use starbase_styles::{Style, Stylize};
 use std::path::PathBuf;
@@ -30,9 +32,45 @@ pub enum CompilerError {
         error: anyhow::Error,
     },
 
+    #[diagnostic(code(compiler::check::failed))]
+    #[error("Type checking failed.")]
+    TypeCheckFailed {
+        /// Raw output of tsc, when no diagnostics could be parsed from it.
+        #[help]
+        output: Option<String>,
+
+        #[related]
+        errors: Vec<TscFileDiagnostics>,
+    },
+
+    #[diagnostic(code(compiler::failed))]
+    #[error("Failed to compile {} files.", .errors.len())]
+    Failed {
+        #[related]
+        errors: Vec<RelatedError>,
+    },
+
     #[diagnostic(code(compiler::declaration::generate_failed))]
     #[error("Failed to generate TypeScript declarations.")]
-    DeclGenerateFailed,
+    DeclGenerateFailed {
+        /// Raw output of tsc, when no diagnostics could be parsed from it.
+        #[help]
+        output: Option<String>,
+
+        #[related]
+        errors: Vec<TscFileDiagnostics>,
+    },
+
+    #[diagnostic(code(compiler::declaration::parse_failed))]
+    #[error("Failed to parse TypeScript declaration {}: {message}", .path.style(Style::Path))]
+    DeclParseFailed { path: PathBuf, message: String },
+
+    #[diagnostic(
+        code(compiler::declaration::typescript_unsupported),
+        help("Set `build.typescript-version` to 4.7 or newer.")
+    )]
+    #[error("TypeScript {} is not supported for generating declarations.", .version.to_string().style(Style::Symbol))]
+    DeclTypeScriptUnsupported { version: Version },
 
     #[diagnostic(code(compiler::module::write_failed))]
     #[error("Failed to create module {}.", .path.style(Style::Path))]
@@ -43,7 +81,42 @@ pub enum CompilerError {
         error: starbase_utils::fs::FsError,
     },
 
+    #[diagnostic(code(compiler::plugin::load_failed))]
+    #[error("Failed to load swc plugin {}: {message}", .path.style(Style::Path))]
+    PluginLoadFailed { path: PathBuf, message: String },
+
+    #[diagnostic(
+        code(compiler::plugin::not_found),
+        help(
+            "Install the plugin's npm package, or check the path relative from the package root."
+        )
+    )]
+    #[error("Unable to find swc plugin {}.", .label.style(Style::Id))]
+    PluginNotFound { label: String },
+
+    #[diagnostic(
+        code(compiler::tsconfig::not_generated),
+        help("Move its settings into tsconfig.espm.json, or pass --force to overwrite it.")
+    )]
+    #[error("Unable to sync {}, as it was not generated by espm.", .path.style(Style::Path))]
+    TsConfigNotGenerated { path: PathBuf },
+
     #[diagnostic(code(compiler::no_javascript_runtime))]
     #[error("Failed to detect a JavaScript runtime. Please install Node or Bun.")]
     NoRuntime,
 }
+
+impl CompilerError {
+    /// Combine multiple reports into a single report, while
+    /// returning a lone report as-is.
+    pub fn from_reports(mut reports: Vec<miette::Report>) -> miette::Report {
+        if reports.len() == 1 {
+            return reports.remove(0);
+        }
+
+        CompilerError::Failed {
+            errors: reports.into_iter().map(|report| report.into()).collect(),
+        }
+        .into()
+    }
+}
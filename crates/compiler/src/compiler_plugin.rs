@@ -0,0 +1,127 @@
+use crate::build_cache::hash_file;
+use crate::compiler_error::CompilerError;
+use espresso_common::EsTarget;
+use once_cell::sync::Lazy;
+use starbase_utils::fs;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use swc::config::{init_plugin_module_cache_once, PluginConfig, PLUGIN_MODULE_CACHE};
+use swc_core::ecma::{ast::Program, visit::VisitMut};
+use swc_plugin_runner::plugin_module_bytes::{PluginModuleBytes, RawPluginModuleBytes};
+use tracing::debug;
+
+// swc never invalidates its cache of compiled plugins, so track the
+// hash of each plugin file that was compiled, keyed by its path
+static LOADED_WASM_PLUGINS: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(Default::default);
+
+/// Information about the module that is being transformed.
+pub struct PluginContext<'module> {
+    pub src_path: &'module Path,
+    pub target: &'module EsTarget,
+}
+
+/// A transform that runs on every module, for embedders of the compiler
+/// to register their own visitors with [`Compiler::add_plugin`](crate::Compiler::add_plugin).
+pub trait CompilerPlugin: Send + Sync {
+    /// Unique name of the plugin. This is part of the build cache key,
+    /// so should change (by including a version) when the output changes.
+    fn name(&self) -> String;
+
+    /// Create a visitor that runs before our own transforms, while
+    /// TypeScript, JSX, and decorators have not been transformed yet.
+    fn before_transform(&self, _context: &PluginContext) -> Option<Box<dyn VisitMut>> {
+        None
+    }
+
+    /// Create a visitor that runs after the module has been transformed
+    /// to the target, but before import aliases and extensions are resolved.
+    fn after_transform(&self, _context: &PluginContext) -> Option<Box<dyn VisitMut>> {
+        None
+    }
+}
+
+/// Plugins that transform modules, in addition to our own visitors.
+#[derive(Clone, Default)]
+pub struct CompilerPlugins {
+    /// Plugins registered by embedders of the compiler.
+    pub native: Vec<Arc<dyn CompilerPlugin>>,
+
+    /// swc Wasm plugins from `[[build.plugins]]`, with resolved paths.
+    pub wasm: Vec<PluginConfig>,
+
+    /// Directory that swc caches compiled Wasm plugins in.
+    pub wasm_cache_dir: Option<PathBuf>,
+}
+
+/// Resolve the `.wasm` file of a swc plugin's npm package, from the `main`
+/// field of its `package.json`, within `node_modules` of any parent directory.
+pub fn resolve_wasm_plugin(name: &str, from_dir: &Path) -> Option<PathBuf> {
+    from_dir.ancestors().find_map(|dir| {
+        let package_dir = dir.join("node_modules").join(name);
+        let package: serde_json::Value =
+            serde_json::from_str(&fs::read_file(package_dir.join("package.json")).ok()?).ok()?;
+
+        let file = package_dir.join(package.get("main")?.as_str()?);
+
+        file.is_file().then_some(file)
+    })
+}
+
+/// Compile a Wasm plugin into swc's in-memory plugin cache, where swc looks for
+/// plugins by their path before transforming. Otherwise swc would read plugins
+/// through its filesystem cache, which is not enabled in our build of swc.
+pub fn load_wasm_plugin(path: &Path, cache_dir: &Option<String>) -> miette::Result<()> {
+    let key = path.to_string_lossy().to_string();
+    let hash = hash_file(path)?;
+    let mut loaded = LOADED_WASM_PLUGINS.lock().unwrap();
+
+    if loaded
+        .get(&key)
+        .is_some_and(|loaded_hash| *loaded_hash == hash)
+    {
+        return Ok(());
+    }
+
+    debug!(path = ?path, "Compiling swc plugin");
+
+    let module = RawPluginModuleBytes::new(key.clone(), fs::read_file_bytes(path)?)
+        .compile_module()
+        .map_err(|error| CompilerError::PluginLoadFailed {
+            path: path.to_path_buf(),
+            message: error.to_string(),
+        })?;
+
+    init_plugin_module_cache_once(true, cache_dir);
+
+    PLUGIN_MODULE_CACHE
+        .inner
+        .get()
+        .expect("Plugin cache should be initialized")
+        .lock()
+        .insert_compiled_module_bytes(key.clone(), module);
+
+    loaded.insert(key, hash);
+
+    Ok(())
+}
+
+// Run the visitors of each plugin in order, as the number of plugins is not
+// known at compile time, so they can't be chained
+pub(crate) struct CompilerPluginsVisitor {
+    visitors: Vec<Box<dyn VisitMut>>,
+}
+
+impl CompilerPluginsVisitor {
+    pub fn new(visitors: Vec<Box<dyn VisitMut>>) -> Self {
+        Self { visitors }
+    }
+}
+
+impl VisitMut for CompilerPluginsVisitor {
+    fn visit_mut_program(&mut self, program: &mut Program) {
+        for visitor in &mut self.visitors {
+            visitor.visit_mut_program(program);
+        }
+    }
+}
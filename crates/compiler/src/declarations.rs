@@ -1,34 +1,73 @@
+use crate::aliases::PathAliases;
 use crate::compiler_error::CompilerError;
-use crate::helpers::{detect_javascript_runtime, OUT_DIR};
-use espresso_common::{EsTarget, Version};
-use espresso_manifest::ManifestBuild;
+use crate::helpers::{create_relative_path, detect_javascript_runtime, OUT_DIR};
+use crate::plugins::ResolveAliasesVisitor;
+use crate::tsc_diagnostics::{parse_tsc_output, TscFileDiagnostics};
+use espresso_common::{EsTarget, PackageName, Version};
+use espresso_manifest::{BuildJsxRuntime, ManifestBuild};
 use espresso_store::{Store, TypeScriptItem};
 use espresso_tsconfig::{
-    Module, ModuleResolution, PartialCompilerOptions, PartialTsConfig, PartialTsConfigExtends,
-    Target as TsTarget,
+    Jsx, Module, ModuleResolution, PartialCompilerOptions, PartialReference, PartialTsConfig,
+    Target as TsTarget, TsConfigLoader,
 };
 use miette::IntoDiagnostic;
 use relative_path::RelativePathBuf;
 use starbase_styles::color;
 use starbase_utils::{fs, glob, json};
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use swc_core::common::{sync::Lrc, SourceMap};
+use swc_core::ecma::{
+    ast::EsVersion,
+    parser::{parse_file_as_module, Syntax, TsConfig},
+    visit::VisitMutWith,
+};
 use tokio::process::Command;
 use tracing::debug;
 
+/// Version of TypeScript used when `build.typescript-version` is not configured.
 pub static TS_VERSION: &str = "5.2.2";
 
+/// Oldest version of TypeScript that supports the options we require.
+pub static MIN_TS_VERSION: &str = "4.7.0";
+
+/// First line of the `tsconfig.json` files written for editors, which
+/// identifies them as generated, and safe to overwrite.
+pub static EDITOR_TSCONFIG_HEADER: &str =
+    "// Generated by espm. Customize tsconfig.espm.json instead, as this file is overwritten.";
+
 pub struct TsConfigState {
     pub path: PathBuf,
     pub project_references: bool,
 }
 
+/// A package within the same workspace, which declarations
+/// of its dependents can reference.
+#[derive(Clone, Debug)]
+pub struct WorkspacePackage {
+    pub build_settings: Arc<ManifestBuild>,
+
+    /// Workspace packages that this package depends on directly.
+    pub dependencies: Vec<PackageName>,
+
+    pub root: PathBuf,
+}
+
+pub type WorkspacePackages = BTreeMap<PackageName, WorkspacePackage>;
+
 /// Represents all TypeScript declarations within the source directory.
 pub struct Declarations {
     pub build_settings: Arc<ManifestBuild>,
     pub package_root: PathBuf,
     pub out_dir: PathBuf,
     pub store: Arc<Store>,
+
+    /// Workspace packages that this package depends on directly.
+    pub dependencies: Vec<PackageName>,
+
+    /// All packages in the workspace, when a multi package repository.
+    pub workspace_packages: Arc<WorkspacePackages>,
 }
 
 impl Declarations {
@@ -43,19 +82,92 @@ impl Declarations {
             package_root,
             out_dir,
             store,
+            dependencies: vec![],
+            workspace_packages: Arc::new(BTreeMap::new()),
         }
     }
 
     pub async fn generate(&self, target: &EsTarget) -> miette::Result<()> {
         debug!("Generating TypeScript declarations");
 
-        let tsconfig_state = self.create_tsconfig(target)?;
-        let tsc_bin = self.load_typescript_binary().await?;
+        let ts_version = self.resolve_typescript_version().await?;
+        let tsconfig_state = self.create_tsconfig(target, &ts_version)?;
+
+        // `tsc --build` skips projects that are up to date, but our declarations are
+        // renamed after being emitted, so always build this package by removing its
+        // build info. Referenced packages are only built again when they have changed.
+        if tsconfig_state.project_references {
+            fs::remove_file(self.get_build_info_file(target))?;
+        }
+
+        self.run_tsc(&ts_version, &tsconfig_state, |output, errors| {
+            CompilerError::DeclGenerateFailed { output, errors }
+        })
+        .await?;
+
+        debug!("Renaming .d.ts files to .d.mts");
+
+        for dts in glob::walk_files(&self.out_dir, ["**/*.d.ts"])? {
+            let mut dmts = dts.clone();
+            dmts.set_extension("mts");
+
+            fs::rename(dts, dmts)?;
+        }
+
+        for dts_map in glob::walk_files(&self.out_dir, ["**/*.d.ts.map"])? {
+            self.rename_declaration_map(dts_map)?;
+        }
+
+        let aliases = Arc::new(PathAliases::load(
+            &self.package_root,
+            &self.package_root.join("src"),
+        )?);
+
+        // tsc does not rewrite aliased imports, so resolve them
+        // to the same specifiers as the output modules
+        if !aliases.is_empty() {
+            debug!("Resolving import aliases in .d.mts files");
+
+            for dmts in glob::walk_files(&self.out_dir, ["**/*.d.mts"])? {
+                self.resolve_declaration_aliases(&aliases, &dmts)?;
+            }
+        }
+
+        debug!("Generated TypeScript declarations");
+
+        Ok(())
+    }
+
+    /// Type check the source and tests directories with tsc, without emitting any files.
+    pub async fn check(&self, target: &EsTarget) -> miette::Result<()> {
+        debug!("Type checking with tsc");
+
+        let ts_version = self.resolve_typescript_version().await?;
+        let tsconfig_state = self.create_check_tsconfig(target, &ts_version)?;
+
+        self.run_tsc(&ts_version, &tsconfig_state, |output, errors| {
+            CompilerError::TypeCheckFailed { output, errors }
+        })
+        .await?;
+
+        debug!("Type checked");
+
+        Ok(())
+    }
+
+    async fn run_tsc(
+        &self,
+        ts_version: &Version,
+        tsconfig_state: &TsConfigState,
+        on_failure: impl FnOnce(Option<String>, Vec<TscFileDiagnostics>) -> CompilerError,
+    ) -> miette::Result<()> {
+        let tsc_bin = self.load_typescript_binary(ts_version).await?;
         let js_runtime = detect_javascript_runtime()?;
 
         debug!(
             tsconfig = ?tsconfig_state.path,
             tsc_bin = ?tsc_bin,
+            ts_version = ts_version.to_string(),
             js_runtime = &js_runtime,
             "Executing {} binary",
             color::shell("tsc"),
@@ -65,22 +177,23 @@ impl Declarations {
         command.arg(tsc_bin);
 
         if tsconfig_state.project_references {
-            command.arg("--build").arg("--force");
+            command.arg("--build");
         } else {
             command.arg("--project");
         }
 
-        let status = command
+        // Capture non-pretty output, so that diagnostics can be parsed
+        let output = command
             .arg(
                 tsconfig_state
                     .path
                     .strip_prefix(&self.package_root)
                     .unwrap(),
             )
+            .arg("--pretty")
+            .arg("false")
             .current_dir(&self.package_root)
-            .spawn()
-            .into_diagnostic()?
-            .wait()
+            .output()
             .await
             .into_diagnostic()?;
 
@@ -90,51 +203,219 @@ impl Declarations {
             color::shell("tsc"),
         );
 
-        if !status.success() {
-            return Err(CompilerError::DeclGenerateFailed)?;
+        if !output.status.success() {
+            let raw_output = format!(
+                "{}{}",
+                String::from_utf8_lossy(&output.stdout),
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let diagnostics = parse_tsc_output(&raw_output, &self.package_root);
+
+            return Err(on_failure(
+                if diagnostics.is_empty() && !raw_output.trim().is_empty() {
+                    Some(raw_output.trim().to_owned())
+                } else {
+                    None
+                },
+                TscFileDiagnostics::group(diagnostics, &self.package_root),
+            ))?;
         }
 
-        debug!("Renaming .d.ts files to .d.mts");
+        Ok(())
+    }
 
-        for dts in glob::walk_files(&self.out_dir, ["**/*.d.ts"])? {
-            let mut dmts = dts.clone();
-            dmts.set_extension("mts");
+    /// Copy declarations that were generated for another target.
+    pub fn copy_from(&self, dir: &Path) -> miette::Result<()> {
+        debug!(from = ?dir, "Copying TypeScript declarations from another target");
 
-            fs::rename(dts, dmts)?;
-        }
+        for file in glob::walk_files(dir, ["**/*.d.mts", "**/*.d.mts.map"])? {
+            let rel_file = file.strip_prefix(dir).into_diagnostic()?;
 
-        debug!("Generated TypeScript declarations");
+            fs::copy_file(&file, self.out_dir.join(rel_file))?;
+        }
 
         Ok(())
     }
 
-    pub fn create_tsconfig(&self, target: &EsTarget) -> miette::Result<TsConfigState> {
+    pub fn create_tsconfig(
+        &self,
+        target: &EsTarget,
+        ts_version: &Version,
+    ) -> miette::Result<TsConfigState> {
+        let mut tsconfig = self.load_base_tsconfig(target)?;
+
+        self.inject_required_options(target, ts_version, &mut tsconfig);
+        self.remap_paths(&mut tsconfig);
+        self.inject_workspace_paths(&mut tsconfig);
+        self.inject_workspace_references(target, ts_version, &mut tsconfig)?;
+
+        let options = tsconfig
+            .compiler_options
+            .get_or_insert_with(PartialCompilerOptions::default);
+
+        if options.composite.is_some_and(|v| v) {
+            options.ts_build_info_file = Some(format!("./tsconfig.{target}.tsbuildinfo"));
+        }
+
+        self.write_tsconfig(format!("tsconfig.{}.json", target), tsconfig)
+    }
+
+    /// Create a tsconfig like [`Declarations::create_tsconfig`], but for type checking
+    /// the source and tests directories without emitting.
+    pub fn create_check_tsconfig(
+        &self,
+        target: &EsTarget,
+        ts_version: &Version,
+    ) -> miette::Result<TsConfigState> {
+        let mut tsconfig = self.load_base_tsconfig(target)?;
+
+        self.inject_required_options(target, ts_version, &mut tsconfig);
+        self.remap_paths(&mut tsconfig);
+        self.inject_workspace_paths(&mut tsconfig);
+
+        tsconfig.include = Some(vec![
+            RelativePathBuf::from("../src/**/*"),
+            RelativePathBuf::from("../tests/**/*"),
+        ]);
+
+        // Only this package is checked, so references
+        // (which require emitting) are not built
+        tsconfig.references = None;
+
+        let options = tsconfig
+            .compiler_options
+            .get_or_insert_with(PartialCompilerOptions::default);
+
+        options.composite = None;
+        options.declaration = None;
+        options.declaration_map = None;
+        options.emit_declaration_only = None;
+        options.no_emit = Some(true);
+        options.out_dir = None;
+        options.root_dir = None;
+
+        self.write_tsconfig("tsconfig.check.json".into(), tsconfig)
+    }
+
+    /// Create a `tsconfig.json` in the package root for editors, with the same compiler
+    /// options as [`Declarations::create_tsconfig`], but including the tests directory.
+    /// Within a workspace, dependencies are referenced through their own `tsconfig.json`.
+    pub fn create_editor_tsconfig(
+        &self,
+        target: &EsTarget,
+        ts_version: &Version,
+    ) -> miette::Result<TsConfigState> {
+        let mut tsconfig = self.load_base_tsconfig(target)?;
+
+        self.inject_required_options(target, ts_version, &mut tsconfig);
+        self.remap_paths(&mut tsconfig);
+        self.inject_workspace_paths(&mut tsconfig);
+
+        tsconfig.include = Some(vec![
+            RelativePathBuf::from("../src/**/*"),
+            RelativePathBuf::from("../tests/**/*"),
+        ]);
+
+        let options = tsconfig
+            .compiler_options
+            .get_or_insert_with(PartialCompilerOptions::default);
+
+        // Tests are outside of the source directory
+        options.root_dir = None;
+
+        // Editors don't emit, but `tsc --build` from the workspace root may,
+        // so keep its output separate from the build's
+        options.out_dir = Some(RelativePathBuf::from("./types"));
+
+        if !self.workspace_packages.is_empty() {
+            options.composite = Some(true);
+            options.ts_build_info_file = Some("./tsconfig.tsbuildinfo".into());
+
+            let tsconfig_dir = self.package_root.join(OUT_DIR);
+            let references = tsconfig.references.get_or_insert_with(Vec::new);
+
+            for (_, package) in self.get_workspace_dependencies() {
+                let path = create_relative_path(&tsconfig_dir, &package.root.join("tsconfig.json"));
+
+                if !references
+                    .iter()
+                    .any(|reference| reference.path.as_ref() == Some(&path))
+                {
+                    references.push(PartialReference {
+                        path: Some(path),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        // Paths are relative from the `.espm` directory,
+        // so make them relative from the package root instead
+        rebase_paths(
+            &mut tsconfig,
+            &self.package_root.join(OUT_DIR),
+            &self.package_root,
+        );
+
+        let tsconfig_file = self.package_root.join("tsconfig.json");
+
+        debug!(
+            tsconfig = ?tsconfig_file,
+            "Creating tsconfig.json for editors"
+        );
+
+        write_editor_tsconfig(&tsconfig_file, &tsconfig)?;
+
+        Ok(TsConfigState {
+            path: tsconfig_file,
+            project_references: tsconfig.references.is_some_and(|refs| !refs.is_empty()),
+        })
+    }
+
+    pub fn get_build_info_file(&self, target: &EsTarget) -> PathBuf {
+        self.package_root
+            .join(OUT_DIR)
+            .join(format!("tsconfig.{target}.tsbuildinfo"))
+    }
+
+    /// Directory in which referenced workspace packages are built by tsc,
+    /// which is private to this package.
+    pub fn get_references_dir(&self, target: &EsTarget) -> PathBuf {
+        self.package_root
+            .join(OUT_DIR)
+            .join("references")
+            .join(target.to_string())
+    }
+
+    fn load_base_tsconfig(&self, target: &EsTarget) -> miette::Result<PartialTsConfig> {
         let custom_tsconfig_file = self.package_root.join("tsconfig.espm.json");
 
-        let mut tsconfig: PartialTsConfig = if custom_tsconfig_file.exists() {
+        if custom_tsconfig_file.exists() {
             debug!(
                 tsconfig = ?custom_tsconfig_file,
                 "A local tsconfig.espm.json exists, using it as a base"
             );
 
-            json::read_file(custom_tsconfig_file)?
-        } else {
-            self.create_default_tsconfig(target)
-        };
+            // Extended files are merged in, with their paths
+            // made relative from the package root
+            return Ok(TsConfigLoader::load(&custom_tsconfig_file)?.config);
+        }
 
-        let tsconfig_file = self
-            .package_root
-            .join(OUT_DIR)
-            .join(format!("tsconfig.{}.json", target));
+        Ok(self.create_default_tsconfig(target))
+    }
+
+    fn write_tsconfig(
+        &self,
+        file_name: String,
+        tsconfig: PartialTsConfig,
+    ) -> miette::Result<TsConfigState> {
+        let tsconfig_file = self.package_root.join(OUT_DIR).join(file_name);
 
         debug!(
             tsconfig = ?tsconfig_file,
             "Creating tsconfig.json"
         );
 
-        self.inject_required_options(target, &mut tsconfig);
-        self.remap_paths(&mut tsconfig);
-
         json::write_file(&tsconfig_file, &tsconfig, true)?;
 
         Ok(TsConfigState {
@@ -146,32 +427,59 @@ impl Declarations {
         })
     }
 
-    pub async fn load_typescript_binary(&self) -> miette::Result<PathBuf> {
-        let version = Version::parse(TS_VERSION).unwrap();
+    pub async fn resolve_typescript_version(&self) -> miette::Result<Version> {
+        let Some(req) = &self.build_settings.typescript_version else {
+            return Ok(Version::parse(TS_VERSION).unwrap());
+        };
+
+        let version = self.store.resolve_npm_version("typescript", req).await?;
+
+        if version < Version::parse(MIN_TS_VERSION).unwrap() {
+            return Err(CompilerError::DeclTypeScriptUnsupported { version })?;
+        }
+
+        Ok(version)
+    }
+
+    pub async fn load_typescript_binary(&self, version: &Version) -> miette::Result<PathBuf> {
         let tarball_url =
             format!("https://registry.npmjs.org/typescript/-/typescript-{version}.tgz");
 
         let store_dir = self
             .store
-            .store_item(&tarball_url, TypeScriptItem { version: &version })
+            .store_item(&tarball_url, TypeScriptItem { version })
             .await?;
 
         Ok(store_dir.join("lib/tsc.js"))
     }
 
     fn create_default_tsconfig(&self, target: &EsTarget) -> PartialTsConfig {
+        let jsx = &self.build_settings.jsx;
+
+        // tsc rejects factories when using the automatic runtime (TS5089)
+        let (jsx_factory, jsx_fragment_factory, jsx_import_source) = match jsx.runtime {
+            BuildJsxRuntime::Automatic => (None, None, jsx.import_source.clone()),
+            BuildJsxRuntime::Classic => (jsx.pragma.clone(), jsx.pragma_frag.clone(), None),
+        };
+
         PartialTsConfig {
             compiler_options: Some(PartialCompilerOptions {
                 allow_arbitrary_extensions: Some(true),
                 allow_importing_ts_extensions: Some(true),
                 allow_js: Some(true),
                 allow_synthetic_default_imports: Some(true),
-                // TODO: Enable once we have source maps
-                declaration_map: Some(false),
                 es_module_interop: Some(true),
-                experimental_decorators: Some(self.build_settings.decorators.is_some()),
+                experimental_decorators: Some(self.build_settings.is_legacy_decorators()),
                 force_consistent_casing_in_file_names: Some(true),
                 isolated_modules: Some(true),
+                jsx: Some(match (jsx.runtime, jsx.development) {
+                    (BuildJsxRuntime::Automatic, false) => Jsx::ReactJsx,
+                    (BuildJsxRuntime::Automatic, true) => Jsx::ReactJsxdev,
+                    (BuildJsxRuntime::Classic, _) => Jsx::React,
+                }),
+                jsx_factory,
+                jsx_fragment_factory,
+                jsx_import_source,
                 lib: Some(vec!["dom".into(), target.to_string()]),
                 no_emit_on_error: Some(true),
                 pretty: Some(true),
@@ -181,7 +489,7 @@ impl Declarations {
                 resolve_package_json_exports: Some(false),
                 resolve_package_json_imports: Some(false),
                 skip_lib_check: Some(true),
-                // TODO: Enable once we have source maps
+                // Module source maps are generated by swc, not tsc
                 source_map: Some(false),
                 strict: Some(true),
                 ..Default::default()
@@ -190,7 +498,12 @@ impl Declarations {
         }
     }
 
-    fn inject_required_options(&self, target: &EsTarget, tsconfig: &mut PartialTsConfig) {
+    fn inject_required_options(
+        &self,
+        target: &EsTarget,
+        ts_version: &Version,
+        tsconfig: &mut PartialTsConfig,
+    ) {
         tsconfig.files = None;
         tsconfig.include = Some(vec![RelativePathBuf::from("../src/**/*")]);
 
@@ -200,13 +513,18 @@ impl Declarations {
 
         options.declaration = Some(true);
         options.declaration_dir = None;
+
+        if self.build_settings.source_maps.is_enabled() {
+            options.declaration_map = Some(true);
+        }
+
         options.emit_declaration_only = Some(true);
         options.no_emit = None;
 
         options.module = Some(match target {
             EsTarget::Es2020 => Module::Es2020,
             EsTarget::Es2021 => Module::Es2020,
-            EsTarget::Es2022 => Module::Es2022,
+            EsTarget::Es2022 | EsTarget::Es2023 | EsTarget::Es2024 => Module::Es2022,
             _ => Module::Es2015,
         });
 
@@ -214,13 +532,29 @@ impl Declarations {
         // or `nodenext`, so default to `node`. This is probably OK since we
         // *don't* want to use the Node.js/npm ecosystem rules.
         // https://devblogs.microsoft.com/typescript/announcing-typescript-5-2/#module-and-moduleresolution-must-match-under-recent-node-js-settings
-        options.module_resolution = Some(ModuleResolution::Node);
+        // TS 6 deprecates `node` (`node10`), where `bundler` is the closest match.
+        options.module_resolution = Some(if ts_version.major >= 6 {
+            ModuleResolution::Bundler
+        } else {
+            ModuleResolution::Node
+        });
+
+        // These options were added in TS 5.0, and older versions error on unknown options
+        if ts_version.major < 5 {
+            options.allow_arbitrary_extensions = None;
+            options.allow_importing_ts_extensions = None;
+            options.resolve_package_json_exports = None;
+            options.resolve_package_json_imports = None;
+            options.verbatim_module_syntax = None;
+        }
 
         options.out_file = None;
         options.out_dir = Some(RelativePathBuf::from(format!("./{target}")));
         options.root_dir = Some(RelativePathBuf::from("../src"));
 
-        options.target = Some(match target {
+        // Newer targets were added in later TS versions (es2023 in 5.0, es2024 in 5.7),
+        // so fallback to `esnext` for older versions, which includes their libs
+        let ts_target = match target {
             EsTarget::Es2015 => TsTarget::Es2015,
             EsTarget::Es2016 => TsTarget::Es2016,
             EsTarget::Es2017 => TsTarget::Es2017,
@@ -229,7 +563,17 @@ impl Declarations {
             EsTarget::Es2020 => TsTarget::Es2020,
             EsTarget::Es2021 => TsTarget::Es2021,
             EsTarget::Es2022 => TsTarget::Es2022,
-        });
+            EsTarget::Es2023 if ts_version.major >= 5 => TsTarget::Es2023,
+            EsTarget::Es2024 if (ts_version.major, ts_version.minor) >= (5, 7) => TsTarget::Es2024,
+            EsTarget::Es2023 | EsTarget::Es2024 => TsTarget::Esnext,
+        };
+        let ts_lib = if ts_target == TsTarget::Esnext {
+            "esnext".to_owned()
+        } else {
+            target.to_string()
+        };
+
+        options.target = Some(ts_target);
 
         // Remove other targets and only use the required target
         if let Some(lib) = options.lib.take() {
@@ -238,7 +582,7 @@ impl Declarations {
                 .filter(|l| !l.to_lowercase().starts_with("es"))
                 .collect::<Vec<_>>();
 
-            new_lib.push(target.to_string());
+            new_lib.push(ts_lib);
 
             // Required for async/generators and old targets, like es2015
             new_lib.push("esnext.asynciterable".into());
@@ -247,34 +591,218 @@ impl Declarations {
         }
     }
 
-    fn remap_paths(&self, tsconfig: &mut PartialTsConfig) {
-        let cd_parent = |value: RelativePathBuf| RelativePathBuf::from("..").join(value);
+    fn get_workspace_dependencies(&self) -> Vec<(&PackageName, &WorkspacePackage)> {
+        self.dependencies
+            .iter()
+            .filter_map(|name| {
+                self.workspace_packages
+                    .get(name)
+                    .map(|package| (name, package))
+            })
+            .collect()
+    }
+
+    /// Return all workspace packages that are depended on, directly or transitively,
+    /// as referenced packages also reference their own dependencies.
+    pub fn get_referenced_packages(&self) -> Vec<(&PackageName, &WorkspacePackage)> {
+        let mut visited = HashSet::new();
+        let mut queue = self.dependencies.iter().collect::<Vec<_>>();
+        let mut packages = vec![];
 
-        let cd_parent_with_check = |value: RelativePathBuf| {
-            if value.as_str().starts_with('.') {
-                cd_parent(value)
-            } else {
-                value
+        while let Some(name) = queue.pop() {
+            if !visited.insert(name) {
+                continue;
             }
+
+            if let Some(package) = self.workspace_packages.get(name) {
+                queue.extend(&package.dependencies);
+                packages.push((name, package));
+            }
+        }
+
+        packages
+    }
+
+    // Allow imports of a sibling package's name to resolve to its sources.
+    // When referenced, tsc will use the declarations built from them instead.
+    fn inject_workspace_paths(&self, tsconfig: &mut PartialTsConfig) {
+        let dependencies = self.get_workspace_dependencies();
+
+        if dependencies.is_empty() {
+            return;
+        }
+
+        let options = tsconfig
+            .compiler_options
+            .get_or_insert_with(PartialCompilerOptions::default);
+
+        // Paths are relative from `baseUrl` when defined, otherwise the tsconfig
+        let tsconfig_dir = self.package_root.join(OUT_DIR);
+        let base_dir = match &options.base_url {
+            Some(base_url) => base_url.to_logical_path(&tsconfig_dir),
+            None => tsconfig_dir,
+        };
+
+        let paths = options.paths.get_or_insert_with(BTreeMap::new);
+
+        for (name, package) in dependencies {
+            let src_dir = create_relative_path(&base_dir, &package.root.join("src"));
+
+            // Paths defined in tsconfig.espm.json take precedence
+            paths
+                .entry(name.to_string())
+                .or_insert_with(|| vec![src_dir.clone()]);
+
+            paths
+                .entry(format!("{name}/*"))
+                .or_insert_with(|| vec![src_dir.join("*")]);
+        }
+    }
+
+    fn inject_workspace_references(
+        &self,
+        target: &EsTarget,
+        ts_version: &Version,
+        tsconfig: &mut PartialTsConfig,
+    ) -> miette::Result<()> {
+        if self.workspace_packages.is_empty() {
+            return Ok(());
+        }
+
+        let options = tsconfig
+            .compiler_options
+            .get_or_insert_with(PartialCompilerOptions::default);
+
+        // Only composite projects can be referenced, so every package in
+        // the workspace is composite, regardless of having dependents
+        options.composite = Some(true);
+
+        // tsc emits declarations for referenced packages, so they are built into
+        // a directory private to this package, instead of their own output
+        // directory, which other builds may be writing to at the same time
+        for (name, package) in self.get_referenced_packages() {
+            self.create_reference_tsconfig(target, ts_version, name, package)?;
+        }
+
+        let mut references = tsconfig.references.take().unwrap_or_default();
+
+        self.inject_reference_paths(
+            target,
+            &self.package_root.join(OUT_DIR),
+            &self.dependencies,
+            &mut references,
+        );
+
+        if !references.is_empty() {
+            tsconfig.references = Some(references);
+        }
+
+        Ok(())
+    }
+
+    fn create_reference_tsconfig(
+        &self,
+        target: &EsTarget,
+        ts_version: &Version,
+        name: &PackageName,
+        package: &WorkspacePackage,
+    ) -> miette::Result<()> {
+        let tsconfig_dir = self.get_reference_dir(target, name);
+        let declarations = Declarations {
+            build_settings: Arc::clone(&package.build_settings),
+            package_root: package.root.clone(),
+            out_dir: tsconfig_dir.clone(),
+            store: Arc::clone(&self.store),
+            dependencies: package.dependencies.clone(),
+            workspace_packages: Arc::clone(&self.workspace_packages),
         };
 
+        let mut tsconfig = declarations.load_base_tsconfig(target)?;
+
+        declarations.inject_required_options(target, ts_version, &mut tsconfig);
+        declarations.remap_paths(&mut tsconfig);
+        declarations.inject_workspace_paths(&mut tsconfig);
+
+        rebase_paths(&mut tsconfig, &package.root.join(OUT_DIR), &tsconfig_dir);
+
+        let options = tsconfig
+            .compiler_options
+            .get_or_insert_with(PartialCompilerOptions::default);
+
+        options.composite = Some(true);
+        options.out_dir = Some(RelativePathBuf::from("."));
+        options.ts_build_info_file = Some("./tsconfig.tsbuildinfo".into());
+
+        let mut references = tsconfig.references.take().unwrap_or_default();
+
+        self.inject_reference_paths(
+            target,
+            &tsconfig_dir,
+            &package.dependencies,
+            &mut references,
+        );
+
+        if !references.is_empty() {
+            tsconfig.references = Some(references);
+        }
+
+        let tsconfig_file = tsconfig_dir.join("tsconfig.json");
+
+        debug!(
+            dependency = name.as_str(),
+            tsconfig = ?tsconfig_file,
+            "Creating tsconfig.json for referenced workspace package"
+        );
+
+        json::write_file(&tsconfig_file, &tsconfig, true)?;
+
+        Ok(())
+    }
+
+    fn get_reference_dir(&self, target: &EsTarget, name: &PackageName) -> PathBuf {
+        self.get_references_dir(target)
+            .join(name.namespace())
+            .join(name.package())
+    }
+
+    fn inject_reference_paths(
+        &self,
+        target: &EsTarget,
+        tsconfig_dir: &Path,
+        dependencies: &[PackageName],
+        references: &mut Vec<PartialReference>,
+    ) {
+        for name in dependencies {
+            if !self.workspace_packages.contains_key(name) {
+                continue;
+            }
+
+            let path = create_tsconfig_path(
+                tsconfig_dir,
+                &self.get_reference_dir(target, name).join("tsconfig.json"),
+            );
+
+            if !references
+                .iter()
+                .any(|reference| reference.path.as_ref() == Some(&path))
+            {
+                references.push(PartialReference {
+                    path: Some(path),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    fn remap_paths(&self, tsconfig: &mut PartialTsConfig) {
+        let cd_parent = |value: RelativePathBuf| RelativePathBuf::from("..").join(value);
+
         let map_list = |list: Vec<RelativePathBuf>| list.into_iter().map(cd_parent).collect();
 
         if let Some(exclude) = tsconfig.exclude.take() {
             tsconfig.exclude = Some(map_list(exclude));
         }
 
-        if let Some(extends) = tsconfig.extends.take() {
-            tsconfig.extends = Some(match extends {
-                PartialTsConfigExtends::String(value) => {
-                    PartialTsConfigExtends::String(cd_parent_with_check(value))
-                }
-                PartialTsConfigExtends::Array(value) => PartialTsConfigExtends::Array(
-                    value.into_iter().map(cd_parent_with_check).collect(),
-                ),
-            });
-        }
-
         if let Some(references) = tsconfig.references.take() {
             tsconfig.references = Some(
                 references
@@ -307,5 +835,224 @@ impl Declarations {
         if let Some(root_dirs) = options.root_dirs.take() {
             options.root_dirs = Some(map_list(root_dirs));
         }
+
+        if let Some(type_roots) = options.type_roots.take() {
+            options.type_roots = Some(map_list(type_roots));
+        }
+    }
+
+    fn resolve_declaration_aliases(
+        &self,
+        aliases: &Arc<PathAliases>,
+        dmts: &Path,
+    ) -> miette::Result<()> {
+        let code = fs::read_file(dmts)?;
+        let cm: Lrc<SourceMap> = Default::default();
+        let fm = cm.new_source_file(dmts.to_path_buf().into(), code.clone());
+
+        let mut module = parse_file_as_module(
+            &fm,
+            Syntax::Typescript(TsConfig {
+                dts: true,
+                ..TsConfig::default()
+            }),
+            EsVersion::latest(),
+            None,
+            &mut vec![],
+        )
+        .map_err(|error| CompilerError::DeclParseFailed {
+            path: dmts.to_path_buf(),
+            message: error.kind().msg().to_string(),
+        })?;
+
+        // Declarations mirror the source directory structure
+        let rel_dir = dmts
+            .parent()
+            .and_then(|dir| dir.strip_prefix(&self.out_dir).ok())
+            .unwrap_or(Path::new(""));
+
+        let mut visitor =
+            ResolveAliasesVisitor::new(Arc::clone(aliases), aliases.src_dir.join(rel_dir));
+
+        module.visit_mut_with(&mut visitor);
+
+        if visitor.rewritten.is_empty() {
+            return Ok(());
+        }
+
+        // Replace the specifiers in place, so the rest of the file is untouched
+        let mut code = code;
+
+        visitor.rewritten.sort_by_key(|(span, _)| span.lo);
+
+        for (span, value) in visitor.rewritten.iter().rev() {
+            let lo = (span.lo.0 - fm.start_pos.0) as usize;
+            let hi = (span.hi.0 - fm.start_pos.0) as usize;
+
+            code.replace_range(lo..hi, &format!("\"{value}\""));
+        }
+
+        fs::write_file(dmts, code)?;
+
+        Ok(())
+    }
+
+    // Declaration maps must be renamed alongside their declarations,
+    // and the references between them updated to match.
+    fn rename_declaration_map(&self, dts_map: PathBuf) -> miette::Result<()> {
+        let dts_map_name = fs::file_name(&dts_map);
+        let dmts_map_name = dts_map_name.replace(".d.ts.map", ".d.mts.map");
+        let dmts_map = dts_map.with_file_name(&dmts_map_name);
+        let dmts = dts_map.with_file_name(dmts_map_name.trim_end_matches(".map"));
+
+        let mut map: serde_json::Value = json::read_file(&dts_map)?;
+
+        if let Some(map) = map.as_object_mut() {
+            map.insert("file".into(), fs::file_name(&dmts).into());
+        }
+
+        json::write_file(dmts_map, &map, false)?;
+        fs::remove_file(&dts_map)?;
+
+        if dmts.exists() {
+            let code = fs::read_file(&dmts)?.replace(
+                &format!("sourceMappingURL={dts_map_name}"),
+                &format!("sourceMappingURL={dmts_map_name}"),
+            );
+
+            fs::write_file(&dmts, code)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Create a solution-style `tsconfig.json` in the workspace root, which only
+/// references the `tsconfig.json` of each package, so that editors can find them.
+pub fn create_solution_tsconfig(
+    workspace_root: &Path,
+    package_roots: &[&Path],
+) -> miette::Result<PathBuf> {
+    let tsconfig_file = workspace_root.join("tsconfig.json");
+
+    let tsconfig = PartialTsConfig {
+        files: Some(vec![]),
+        references: Some(
+            package_roots
+                .iter()
+                .map(|root| PartialReference {
+                    path: Some(RelativePathBuf::from(".").join(create_relative_path(
+                        workspace_root,
+                        &root.join("tsconfig.json"),
+                    ))),
+                    ..Default::default()
+                })
+                .collect(),
+        ),
+        ..Default::default()
+    };
+
+    debug!(
+        tsconfig = ?tsconfig_file,
+        "Creating solution tsconfig.json for editors"
+    );
+
+    write_editor_tsconfig(&tsconfig_file, &tsconfig)?;
+
+    Ok(tsconfig_file)
+}
+
+/// Return true if the `tsconfig.json` file does not exist, or was generated
+/// for editors by `espm`, and can be overwritten.
+pub fn is_generated_tsconfig(path: &Path) -> miette::Result<bool> {
+    if !path.exists() {
+        return Ok(true);
+    }
+
+    Ok(fs::read_file(path)?.starts_with(EDITOR_TSCONFIG_HEADER))
+}
+
+// tsconfig.json files may contain comments, so mark our files with one
+fn write_editor_tsconfig(path: &Path, tsconfig: &PartialTsConfig) -> miette::Result<()> {
+    let json = serde_json::to_string_pretty(tsconfig).into_diagnostic()?;
+
+    fs::write_file(path, format!("{EDITOR_TSCONFIG_HEADER}\n{json}"))?;
+
+    Ok(())
+}
+
+// Make a path that is relative from one directory, relative from another directory
+fn rebase_path(value: RelativePathBuf, from_dir: &Path, to_dir: &Path) -> RelativePathBuf {
+    create_tsconfig_path(to_dir, &value.to_logical_path(from_dir))
+}
+
+// Paths in a tsconfig are explicitly relative, like `./src` instead of `src`
+fn create_tsconfig_path(tsconfig_dir: &Path, to: &Path) -> RelativePathBuf {
+    let path = create_relative_path(tsconfig_dir, to);
+
+    if path.as_str().is_empty() {
+        RelativePathBuf::from(".")
+    } else if path.as_str() == ".." || path.as_str().starts_with("../") {
+        path
+    } else {
+        RelativePathBuf::from(".").join(path)
+    }
+}
+
+// Paths in a tsconfig are relative from the directory it's in, so make them
+// relative from another directory, for writing the tsconfig there instead
+fn rebase_paths(tsconfig: &mut PartialTsConfig, from_dir: &Path, to_dir: &Path) {
+    let rebase = |value: RelativePathBuf| rebase_path(value, from_dir, to_dir);
+
+    let map_list = |list: Vec<RelativePathBuf>| list.into_iter().map(rebase).collect();
+
+    if let Some(include) = tsconfig.include.take() {
+        tsconfig.include = Some(map_list(include));
+    }
+
+    if let Some(exclude) = tsconfig.exclude.take() {
+        tsconfig.exclude = Some(map_list(exclude));
+    }
+
+    if let Some(references) = tsconfig.references.take() {
+        tsconfig.references = Some(
+            references
+                .into_iter()
+                .map(|mut rf| {
+                    rf.path = rf.path.map(rebase);
+                    rf
+                })
+                .collect(),
+        );
+    }
+
+    let options = tsconfig
+        .compiler_options
+        .get_or_insert_with(PartialCompilerOptions::default);
+
+    options.base_url = options.base_url.take().map(rebase);
+    options.out_dir = options.out_dir.take().map(rebase);
+    options.root_dir = options.root_dir.take().map(rebase);
+
+    options.ts_build_info_file = options
+        .ts_build_info_file
+        .take()
+        .map(|file| rebase(RelativePathBuf::from(file)).to_string());
+
+    if let Some(paths) = options.paths.take() {
+        options.paths = Some(
+            paths
+                .into_iter()
+                .map(|(key, list)| (key, map_list(list)))
+                .collect(),
+        );
+    }
+
+    if let Some(root_dirs) = options.root_dirs.take() {
+        options.root_dirs = Some(map_list(root_dirs));
+    }
+
+    if let Some(type_roots) = options.type_roots.take() {
+        options.type_roots = Some(map_list(type_roots));
     }
 }
@@ -1,5 +1,6 @@
 use crate::compiler_error::CompilerError;
 use cached::proc_macro::cached;
+use relative_path::RelativePathBuf;
 use starbase_utils::string_vec;
 use std::env;
 use std::path::Path;
@@ -58,8 +59,38 @@ pub fn detect_javascript_runtime() -> miette::Result<String> {
     Err(CompilerError::NoRuntime)?
 }
 
+/// Number of available cores, used to bound how many files are compiled in parallel.
+pub fn get_default_concurrency() -> usize {
+    std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(4)
+}
+
 pub fn has_extension(path: &Path, exts: &[&str]) -> bool {
     path.extension()
         .map(|ext| exts.iter().any(|e| ext.eq_ignore_ascii_case(e)))
         .unwrap_or(false)
 }
+
+/// Create a relative path (with forward slashes) from a directory to another path.
+pub fn create_relative_path(from_dir: &Path, to: &Path) -> RelativePathBuf {
+    let from_components = from_dir.components().collect::<Vec<_>>();
+    let to_components = to.components().collect::<Vec<_>>();
+    let common = from_components
+        .iter()
+        .zip(&to_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut path = RelativePathBuf::new();
+
+    for _ in common..from_components.len() {
+        path.push("..");
+    }
+
+    for component in &to_components[common..] {
+        path.push(component.as_os_str().to_string_lossy().as_ref());
+    }
+
+    path
+}
@@ -1,14 +1,24 @@
+mod aliases;
 mod asset;
+mod build_cache;
+mod build_report;
 mod compiler;
 mod compiler_error;
+mod compiler_plugin;
 mod declarations;
 mod helpers;
 mod module;
 mod plugins;
+mod tsc_diagnostics;
 
+pub use aliases::*;
 pub use asset::*;
+pub use build_cache::*;
+pub use build_report::*;
 pub use compiler::*;
 pub use compiler_error::*;
+pub use compiler_plugin::*;
 pub use declarations::*;
 pub use helpers::*;
 pub use module::*;
+pub use tsc_diagnostics::*;
@@ -1,79 +1,153 @@
+use crate::aliases::PathAliases;
 use crate::compiler_error::CompilerError;
-use crate::helpers::has_extension;
-use crate::plugins::{AddMjsExtensionVisitor, DetectCjsVisitor};
+use crate::compiler_plugin::{CompilerPlugins, CompilerPluginsVisitor, PluginContext};
+use crate::helpers::{create_relative_path, has_extension};
+use crate::plugins::{AddMjsExtensionVisitor, DetectCjsVisitor, ResolveAliasesVisitor};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
 use espresso_common::EsTarget;
-use espresso_manifest::{BuildDecorators, ManifestBuild};
+use espresso_manifest::{BuildDecorators, BuildJsxRuntime, BuildSourceMaps, ManifestBuild};
+use miette::IntoDiagnostic;
 use starbase_utils::fs;
 use std::path::PathBuf;
 use std::sync::Arc;
 use swc::config::{
-    CallerOptions, Config, DecoratorVersion, IsModule, JscConfig, ModuleConfig, Options,
-    TransformConfig,
+    CallerOptions, Config, DecoratorVersion, IsModule, JsMinifyCommentOption,
+    JsMinifyFormatOptions, JsMinifyOptions, JscConfig, JscExperimental, ModuleConfig, Options,
+    SourceMapsConfig, TransformConfig,
 };
 use swc::{try_with_handler, Compiler as SwcCompiler, HandlerOpts};
+use swc_config::config_types::BoolOrDataConfig;
 use swc_core::common::GLOBALS;
 use swc_core::ecma::{
     ast::EsVersion,
     parser::{EsConfig, Syntax, TsConfig},
+    transforms::react::{Options as ReactOptions, Runtime as ReactRuntime},
     // transforms::base::pass::noop,
     visit::as_folder,
 };
-// use swc_visit::chain;
+use swc_visit::chain;
 use tracing::debug;
 
 /// Represents a single module file, either JavaScript or TypeScript.
 pub struct Module {
+    pub aliases: Arc<PathAliases>,
     pub build_settings: Arc<ManifestBuild>,
     pub out_path: PathBuf,
     pub src_path: PathBuf,
+
+    /// Plugins to transform the module with, in addition to our own.
+    pub plugins: Arc<CompilerPlugins>,
 }
 
 impl Module {
-    pub fn new(src_path: PathBuf, out_path: PathBuf, build_settings: Arc<ManifestBuild>) -> Self {
+    pub fn new(
+        src_path: PathBuf,
+        out_path: PathBuf,
+        build_settings: Arc<ManifestBuild>,
+        aliases: Arc<PathAliases>,
+    ) -> Self {
         Self {
+            aliases,
             build_settings,
             out_path,
             src_path,
+            plugins: Arc::new(CompilerPlugins::default()),
         }
     }
 
     pub fn is_legacy_decorators(&self) -> bool {
         self.build_settings.is_legacy_decorators()
-            || self.build_settings.decorators.is_some() && self.is_typescript()
     }
 
     pub fn is_typescript(&self) -> bool {
         has_extension(&self.src_path, &["ts", "tsx", "mts"])
     }
 
+    pub fn get_source_map_path(&self) -> PathBuf {
+        let mut path = self.out_path.clone().into_os_string();
+        path.push(".map");
+        path.into()
+    }
+
+    /// Path to the source file, relative from the output directory of this module,
+    /// which is used as the source map `sources`.
+    pub fn get_source_map_source(&self) -> String {
+        create_relative_path(
+            self.out_path
+                .parent()
+                .expect("Module requires a parent directory!"),
+            &self.src_path,
+        )
+        .to_string()
+    }
+
     pub fn create_transform_options(&self, target: &EsTarget) -> Options {
         let decorators = self.build_settings.decorators.as_ref();
 
-        // TODO: react
+        let jsx = &self.build_settings.jsx;
+
+        // Pragmas only apply to the classic runtime
+        let (runtime, pragma, pragma_frag) = match jsx.runtime {
+            BuildJsxRuntime::Automatic => (ReactRuntime::Automatic, None, None),
+            BuildJsxRuntime::Classic => (
+                ReactRuntime::Classic,
+                jsx.pragma.clone(),
+                jsx.pragma_frag.clone(),
+            ),
+        };
+
+        let react = ReactOptions {
+            development: Some(jsx.development),
+            import_source: jsx.import_source.clone(),
+            pragma,
+            pragma_frag,
+            runtime: Some(runtime),
+            ..ReactOptions::default()
+        };
+
         let transform = TransformConfig {
             const_modules: None,
-            decorator_metadata: decorators.is_some().into(),
-            decorator_version: if self.is_legacy_decorators() {
-                Some(DecoratorVersion::V202112)
-            } else {
-                decorators.map(|dec| match dec {
-                    BuildDecorators::Legacy => DecoratorVersion::V202112,
-                })
-            },
+            // Metadata is only emitted by TypeScript's legacy decorators
+            decorator_metadata: self.is_legacy_decorators().into(),
+            decorator_version: decorators.map(|dec| match dec {
+                BuildDecorators::Legacy => DecoratorVersion::V202112,
+                BuildDecorators::Standard => DecoratorVersion::V202203,
+            }),
             legacy_decorator: self.is_legacy_decorators().into(),
             optimizer: None,
+            react,
             use_define_for_class_fields: true.into(),
             ..TransformConfig::default()
         };
 
-        // TODO: root, paths, baseUrl
+        let minify = self.build_settings.minify.get_options();
+        let keep_class_names = minify
+            .as_ref()
+            .map(|options| options.keep_class_names)
+            .unwrap_or(true);
+
         let jsc = JscConfig {
             // assumptions: Some(Assumptions::all()),
             external_helpers: false.into(),
-            keep_class_names: true.into(),
+            keep_class_names: keep_class_names.into(),
             loose: false.into(),
-            minify: None,
-            preserve_all_comments: true.into(),
+            minify: minify.as_ref().map(|options| JsMinifyOptions {
+                compress: BoolOrDataConfig::from_bool(options.compress),
+                mangle: BoolOrDataConfig::from_bool(options.mangle),
+                keep_classnames: options.keep_class_names,
+                module: true,
+                // Only license comments are preserved
+                format: JsMinifyFormatOptions {
+                    comments: BoolOrDataConfig::from_obj(
+                        JsMinifyCommentOption::PreserveSomeComments,
+                    ),
+                    ..JsMinifyFormatOptions::default()
+                },
+                ..JsMinifyOptions::default()
+            }),
+            // Comments are stripped when minifying
+            preserve_all_comments: minify.is_none().into(),
             syntax: Some(if self.is_typescript() {
                 Syntax::Typescript(TsConfig {
                     decorators: decorators.is_some(),
@@ -103,8 +177,25 @@ impl Module {
                 EsTarget::Es2020 => EsVersion::Es2020,
                 EsTarget::Es2021 => EsVersion::Es2021,
                 EsTarget::Es2022 => EsVersion::Es2022,
+                // swc has no versions past es2022, but these only add syntax that
+                // isn't downleveled (hashbangs and regex `v` flags), so compile as-is
+                EsTarget::Es2023 | EsTarget::Es2024 => EsVersion::EsNext,
             }),
             transform: Some(transform).into(),
+            // Wasm plugins are ran by swc itself
+            experimental: if self.plugins.wasm.is_empty() {
+                JscExperimental::default()
+            } else {
+                JscExperimental {
+                    cache_root: self
+                        .plugins
+                        .wasm_cache_dir
+                        .as_ref()
+                        .map(|dir| dir.to_string_lossy().to_string()),
+                    plugins: Some(self.plugins.wasm.clone()),
+                    ..JscExperimental::default()
+                }
+            },
             ..JscConfig::default()
         };
 
@@ -113,7 +204,7 @@ impl Module {
                 // env,
                 is_module: Some(IsModule::Bool(true)),
                 jsc,
-                minify: false.into(),
+                minify: minify.is_some().into(),
                 module: Some(ModuleConfig::Es6),
                 ..Config::default()
             },
@@ -123,13 +214,25 @@ impl Module {
             env_name: "production".into(),
             filename: fs::file_name(&self.src_path),
             output_path: Some(self.out_path.clone()),
+            source_file_name: if self.build_settings.source_maps.is_enabled() {
+                Some(self.get_source_map_source())
+            } else {
+                None
+            },
+            // Maps are always returned separately, so that they can be finalized
+            // before being written, and are inlined by us when required
+            source_maps: if self.build_settings.source_maps.is_enabled() {
+                Some(SourceMapsConfig::Bool(true))
+            } else {
+                None
+            },
             swcrc: false,
             swcrc_roots: None,
             ..Options::default()
         }
     }
 
-    pub async fn transform(&self, compiler: &SwcCompiler, target: &EsTarget) -> miette::Result<()> {
+    pub fn transform(&self, compiler: &SwcCompiler, target: &EsTarget) -> miette::Result<()> {
         debug!(src = ?self.src_path, out = ?self.out_path, "Transforming module");
 
         let input =
@@ -138,6 +241,11 @@ impl Module {
                 error,
             })?;
 
+        let context = PluginContext {
+            src_path: &self.src_path,
+            target,
+        };
+
         let output = try_with_handler(
             compiler.cm.clone(),
             HandlerOpts {
@@ -154,8 +262,39 @@ impl Module {
                         handler,
                         &self.create_transform_options(target),
                         Default::default(),
-                        |_| as_folder(DetectCjsVisitor),
-                        |_| as_folder(AddMjsExtensionVisitor),
+                        |_| {
+                            chain!(
+                                as_folder(DetectCjsVisitor),
+                                as_folder(CompilerPluginsVisitor::new(
+                                    self.plugins
+                                        .native
+                                        .iter()
+                                        .filter_map(|plugin| plugin.before_transform(&context))
+                                        .collect(),
+                                ))
+                            )
+                        },
+                        |_| {
+                            // Plugins may add imports, and aliases resolve
+                            // to .mjs files, so both must run first
+                            chain!(
+                                as_folder(CompilerPluginsVisitor::new(
+                                    self.plugins
+                                        .native
+                                        .iter()
+                                        .filter_map(|plugin| plugin.after_transform(&context))
+                                        .collect(),
+                                )),
+                                as_folder(ResolveAliasesVisitor::new(
+                                    Arc::clone(&self.aliases),
+                                    self.src_path
+                                        .parent()
+                                        .expect("Module requires a parent directory!")
+                                        .to_path_buf(),
+                                )),
+                                as_folder(AddMjsExtensionVisitor)
+                            )
+                        },
                     )
                 })
             },
@@ -165,13 +304,51 @@ impl Module {
             error,
         })?;
 
-        fs::write_file(&self.out_path, output.code).map_err(|error| {
-            CompilerError::ModuleWriteFailed {
-                path: self.src_path.clone(),
-                error,
+        let mut code = output.code;
+
+        if let Some(map) = output.map {
+            let map = self.finalize_source_map(&map)?;
+
+            if self.build_settings.source_maps == BuildSourceMaps::Inline {
+                code.push_str(&format!(
+                    "\n//# sourceMappingURL=data:application/json;base64,{}\n",
+                    STANDARD.encode(map)
+                ));
+            } else {
+                let map_path = self.get_source_map_path();
+
+                code.push_str(&format!(
+                    "\n//# sourceMappingURL={}\n",
+                    fs::file_name(&map_path)
+                ));
+
+                fs::write_file(&map_path, map).map_err(|error| {
+                    CompilerError::ModuleWriteFailed {
+                        path: self.src_path.clone(),
+                        error,
+                    }
+                })?;
             }
+        }
+
+        fs::write_file(&self.out_path, code).map_err(|error| CompilerError::ModuleWriteFailed {
+            path: self.src_path.clone(),
+            error,
         })?;
 
         Ok(())
     }
+
+    // swc does not set the generated file, and the sources may not be
+    // relative, so ensure both point to the correct files, for inline maps too.
+    fn finalize_source_map(&self, map: &str) -> miette::Result<String> {
+        let mut map: serde_json::Value = serde_json::from_str(map).into_diagnostic()?;
+
+        if let Some(map) = map.as_object_mut() {
+            map.insert("file".into(), fs::file_name(&self.out_path).into());
+            map.insert("sources".into(), vec![self.get_source_map_source()].into());
+        }
+
+        serde_json::to_string(&map).into_diagnostic()
+    }
 }
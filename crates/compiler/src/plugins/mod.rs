@@ -1,5 +1,7 @@
 mod add_mjs_ext;
 mod detect_cjs;
+mod resolve_aliases;
 
 pub use add_mjs_ext::*;
 pub use detect_cjs::*;
+pub use resolve_aliases::*;
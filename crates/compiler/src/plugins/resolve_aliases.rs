@@ -0,0 +1,77 @@
+use crate::aliases::PathAliases;
+use std::path::PathBuf;
+use std::sync::Arc;
+use swc_core::common::Span;
+use swc_core::ecma::{
+    ast::{CallExpr, ExportAll, Expr, ImportDecl, Lit, NamedExport, Str, TsImportType},
+    visit::{VisitMut, VisitMutWith},
+};
+
+pub struct ResolveAliasesVisitor {
+    pub aliases: Arc<PathAliases>,
+    pub from_dir: PathBuf,
+
+    /// Spans of the specifiers that were rewritten, and their new value.
+    pub rewritten: Vec<(Span, String)>,
+}
+
+impl ResolveAliasesVisitor {
+    pub fn new(aliases: Arc<PathAliases>, from_dir: PathBuf) -> Self {
+        Self {
+            aliases,
+            from_dir,
+            rewritten: vec![],
+        }
+    }
+
+    fn resolve(&mut self, source: &mut Str) {
+        if let Some(value) = self.aliases.resolve(&source.value, &self.from_dir) {
+            self.rewritten.push((source.span, value.clone()));
+
+            source.value = value.into();
+            source.raw = None;
+        }
+    }
+}
+
+impl VisitMut for ResolveAliasesVisitor {
+    // export * from '@/file'
+    fn visit_mut_export_all(&mut self, n: &mut ExportAll) {
+        n.visit_mut_children_with(self);
+        self.resolve(&mut n.src);
+    }
+
+    // import '@/file'
+    // import ... from '@/file'
+    fn visit_mut_import_decl(&mut self, n: &mut ImportDecl) {
+        n.visit_mut_children_with(self);
+        self.resolve(&mut n.src);
+    }
+
+    // export {} from '@/file'
+    // export * as ns from '@/file'
+    fn visit_mut_named_export(&mut self, n: &mut NamedExport) {
+        n.visit_mut_children_with(self);
+
+        if let Some(src) = &mut n.src {
+            self.resolve(src);
+        }
+    }
+
+    // import("@/file");
+    fn visit_mut_call_expr(&mut self, n: &mut CallExpr) {
+        n.visit_mut_children_with(self);
+
+        if n.callee.is_import() && n.args.len() == 1 {
+            if let Expr::Lit(Lit::Str(arg)) = &mut (*n.args[0].expr) {
+                self.resolve(arg);
+            }
+        }
+    }
+
+    // type T = import("@/file").T;
+    fn visit_mut_ts_import_type(&mut self, n: &mut TsImportType) {
+        n.visit_mut_children_with(self);
+        self.resolve(&mut n.arg);
+    }
+}
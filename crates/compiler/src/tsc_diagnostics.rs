@@ -0,0 +1,253 @@
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceCode, SourceSpan};
+use relative_path::RelativePathBuf;
+use starbase_styles::{Style, Stylize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+
+/// A single diagnostic reported by tsc, parsed from its non-pretty output.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TscDiagnostic {
+    /// File the diagnostic was reported in, relative from the package root.
+    /// Diagnostics about the project itself do not have a file.
+    pub file: Option<RelativePathBuf>,
+
+    /// Line and column, starting from 1.
+    pub line: usize,
+    pub column: usize,
+
+    /// Either `error`, `warning`, or `message`.
+    pub category: String,
+
+    /// Diagnostic code, like `TS2322`.
+    pub code: String,
+
+    pub message: String,
+}
+
+/// Parse each `file(line,col): error TSxxxx: message` from tsc's output.
+/// Messages that span multiple lines are indented, so are joined
+/// with the previous diagnostic.
+pub fn parse_tsc_output(output: &str, package_root: &Path) -> Vec<TscDiagnostic> {
+    let mut diagnostics: Vec<TscDiagnostic> = vec![];
+
+    for line in output.lines() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            if let Some(last) = diagnostics.last_mut() {
+                last.message.push('\n');
+                last.message.push_str(line.trim());
+            }
+
+            continue;
+        }
+
+        if let Some(diagnostic) = parse_tsc_line(line, package_root) {
+            diagnostics.push(diagnostic);
+        }
+    }
+
+    diagnostics
+}
+
+fn parse_tsc_line(line: &str, package_root: &Path) -> Option<TscDiagnostic> {
+    let is_global = ["error TS", "warning TS", "message TS"]
+        .iter()
+        .any(|prefix| line.starts_with(prefix));
+
+    let (location, rest) = if is_global {
+        (None, line)
+    } else {
+        let index = line.find("): ")?;
+
+        (Some(&line[..=index]), &line[index + 3..])
+    };
+
+    let (category, rest) = rest.split_once(' ')?;
+
+    if !matches!(category, "error" | "warning" | "message") {
+        return None;
+    }
+
+    let (code, message) = rest.split_once(": ")?;
+
+    if !code.starts_with("TS") {
+        return None;
+    }
+
+    let mut diagnostic = TscDiagnostic {
+        file: None,
+        line: 0,
+        column: 0,
+        category: category.to_owned(),
+        code: code.to_owned(),
+        message: message.to_owned(),
+    };
+
+    if let Some(location) = location {
+        let (file, position) = location.strip_suffix(')')?.rsplit_once('(')?;
+        let (line, column) = position.split_once(',')?;
+
+        diagnostic.file = Some(normalize_file(file, package_root));
+        diagnostic.line = line.parse().ok()?;
+        diagnostic.column = column.parse().ok()?;
+    }
+
+    Some(diagnostic)
+}
+
+// tsc reports files relative from the working directory (the package root),
+// but may report absolute paths when they are outside of it
+fn normalize_file(file: &str, package_root: &Path) -> RelativePathBuf {
+    let path = Path::new(file);
+    let path = path.strip_prefix(package_root).unwrap_or(path);
+
+    RelativePathBuf::from_path(path)
+        .unwrap_or_else(|_| RelativePathBuf::from(file.replace('\\', "/")))
+        .normalize()
+}
+
+/// All diagnostics reported by tsc for a single file, rendered with source spans.
+#[derive(Debug)]
+pub struct TscFileDiagnostics {
+    pub file: Option<RelativePathBuf>,
+    pub diagnostics: Vec<TscDiagnostic>,
+
+    source: Option<NamedSource>,
+    spans: Vec<SourceSpan>,
+}
+
+impl TscFileDiagnostics {
+    /// Group diagnostics by file, and load the contents of each file
+    /// so that diagnostics can be labeled in the source.
+    pub fn group(diagnostics: Vec<TscDiagnostic>, package_root: &Path) -> Vec<TscFileDiagnostics> {
+        let mut groups: BTreeMap<Option<RelativePathBuf>, Vec<TscDiagnostic>> = BTreeMap::new();
+
+        for diagnostic in diagnostics {
+            groups
+                .entry(diagnostic.file.clone())
+                .or_default()
+                .push(diagnostic);
+        }
+
+        groups
+            .into_iter()
+            .map(|(file, mut diagnostics)| {
+                diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+
+                let contents = file
+                    .as_ref()
+                    .and_then(|file| std::fs::read_to_string(file.to_path(package_root)).ok());
+
+                let (source, spans) = match (&file, contents) {
+                    (Some(file), Some(contents)) => {
+                        let spans = diagnostics
+                            .iter()
+                            .map(|diagnostic| {
+                                find_span(&contents, diagnostic.line, diagnostic.column)
+                            })
+                            .collect();
+
+                        (Some(NamedSource::new(file.as_str(), contents)), spans)
+                    }
+                    _ => (None, vec![]),
+                };
+
+                TscFileDiagnostics {
+                    file,
+                    diagnostics,
+                    source,
+                    spans,
+                }
+            })
+            .collect()
+    }
+}
+
+// tsc does not report the length of a diagnostic, so
+// label the word (or character) at the position instead
+fn find_span(contents: &str, line: usize, column: usize) -> SourceSpan {
+    let mut offset = 0;
+
+    for (index, text) in contents.split_inclusive('\n').enumerate() {
+        if index + 1 == line {
+            let start = text
+                .char_indices()
+                .nth(column.saturating_sub(1))
+                .map(|(index, _)| index)
+                .unwrap_or_default();
+
+            let length = text[start..]
+                .chars()
+                .take_while(|ch| ch.is_alphanumeric() || *ch == '_' || *ch == '$')
+                .map(char::len_utf8)
+                .sum::<usize>()
+                .max(1);
+
+            return (offset + start, length).into();
+        }
+
+        offset += text.len();
+    }
+
+    (offset, 0).into()
+}
+
+impl fmt::Display for TscFileDiagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let (Some(file), Some(_)) = (&self.file, &self.source) {
+            return write!(
+                f,
+                "Found {} issue(s) in {}.",
+                self.diagnostics.len(),
+                file.to_string().style(Style::Path)
+            );
+        }
+
+        let messages = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| match &diagnostic.file {
+                Some(file) => format!(
+                    "{}({},{}): {}: {}",
+                    file.to_string().style(Style::Path),
+                    diagnostic.line,
+                    diagnostic.column,
+                    diagnostic.code,
+                    diagnostic.message
+                ),
+                None => format!("{}: {}", diagnostic.code, diagnostic.message),
+            })
+            .collect::<Vec<_>>();
+
+        write!(f, "{}", messages.join("\n"))
+    }
+}
+
+impl std::error::Error for TscFileDiagnostics {}
+
+impl Diagnostic for TscFileDiagnostics {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new("compiler::declaration::tsc"))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.source.as_ref().map(|source| source as &dyn SourceCode)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.source.as_ref()?;
+
+        Some(Box::new(self.diagnostics.iter().zip(&self.spans).map(
+            |(diagnostic, span)| {
+                LabeledSpan::new_with_span(
+                    Some(format!("{}: {}", diagnostic.code, diagnostic.message)),
+                    *span,
+                )
+            },
+        )))
+    }
+}
@@ -0,0 +1,7 @@
+export function App() {
+	return (
+		<>
+			<div className="app">Hello</div>
+		</>
+	);
+}
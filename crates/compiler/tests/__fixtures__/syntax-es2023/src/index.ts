@@ -0,0 +1,5 @@
+#!/usr/bin/env node
+
+export const last = [1, 2, 3].findLast((value) => value > 1);
+
+export const lastIndex = [1, 2, 3].findLastIndex((value) => value > 1);
@@ -0,0 +1,14 @@
+function logged<This, Args extends any[], Return>(
+	method: (this: This, ...args: Args) => Return,
+	context: ClassMethodDecoratorContext<This>,
+) {
+	return method;
+}
+
+function sealed(target: Function, context: ClassDecoratorContext) {}
+
+@sealed
+export class Foo {
+	@logged
+	method() {}
+}
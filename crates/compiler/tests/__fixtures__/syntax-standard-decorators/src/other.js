@@ -0,0 +1,5 @@
+@sealed
+export class Foo {
+	@logged
+	method() {}
+}
@@ -1,11 +1,15 @@
 mod utils;
 
-use espresso_common::EsTarget;
-use espresso_compiler::Declarations;
+use espresso_common::{EsTarget, PackageName, Version};
+use espresso_compiler::{
+    create_solution_tsconfig, is_generated_tsconfig, Declarations, WorkspacePackage,
+    WorkspacePackages, EDITOR_TSCONFIG_HEADER, TS_VERSION,
+};
 use espresso_manifest::{BuildOptimizePng, ManifestBuild};
 use espresso_package::Package;
 use espresso_store::Store;
-use starbase_sandbox::{assert_snapshot, create_sandbox, locate_fixture};
+use espresso_tsconfig::strip_jsonc;
+use starbase_sandbox::{assert_snapshot, create_empty_sandbox, create_sandbox, locate_fixture};
 use std::fs;
 use std::sync::Arc;
 use utils::*;
@@ -36,6 +40,701 @@ mod compile_modules {
             fs::read_to_string(sandbox.path().join(".espm/es2022/helpers.mjs")).unwrap()
         );
     }
+
+    #[tokio::test]
+    async fn compiles_with_a_single_worker() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let mut compiler = create_compiler(sandbox.path(), &package);
+
+        compiler.concurrency = 1;
+
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        assert!(out_dir.join("index.mjs").exists());
+        assert!(out_dir.join("helpers.mjs").exists());
+    }
+
+    #[tokio::test]
+    async fn collects_all_errors() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file("src/a.js", "export const a = require('a');\n");
+        sandbox.create_file("src/b.js", "export const b = require('b');\n");
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let error = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.to_string(), "Failed to compile 2 files.");
+        assert_eq!(error.related().unwrap().count(), 2);
+    }
+}
+
+mod build_cache {
+    use super::*;
+    use espresso_compiler::{hash_dependencies, hash_settings, CompilerPlugins};
+    use espresso_manifest::BuildMinify;
+
+    #[tokio::test]
+    async fn writes_cache_for_target() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+
+        create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+
+        let cache = read_file(sandbox.path().join(".espm/cache.es2018.json"));
+
+        assert!(cache.contains("\"index.js\""));
+        assert!(cache.contains("\"helpers.js\""));
+        assert!(!sandbox.path().join(".espm/cache.es2019.json").exists());
+    }
+
+    #[tokio::test]
+    async fn skips_unchanged_files() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        // Mark the outputs so we can detect a recompile
+        fs::write(out_dir.join("index.mjs"), "marker").unwrap();
+        fs::write(out_dir.join("helpers.mjs"), "marker").unwrap();
+
+        sandbox.create_file("src/index.js", "export const changed = true;\n");
+
+        compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        assert!(read_file(out_dir.join("index.mjs")).contains("changed"));
+        assert_eq!(read_file(out_dir.join("helpers.mjs")), "marker");
+    }
+
+    #[tokio::test]
+    async fn rebuilds_everything_when_cleared() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        fs::write(out_dir.join("helpers.mjs"), "marker").unwrap();
+
+        compiler.clear_cache(&[EsTarget::Es2018]).unwrap();
+
+        assert!(!sandbox.path().join(".espm/cache.es2018.json").exists());
+
+        compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        assert_ne!(read_file(out_dir.join("helpers.mjs")), "marker");
+    }
+
+    #[tokio::test]
+    async fn rebuilds_everything_when_settings_change() {
+        let sandbox = create_sandbox("js-files");
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+
+        fs::write(out_dir.join("helpers.mjs"), "marker").unwrap();
+
+        package.manifest.build.minify = BuildMinify::Enabled(true);
+
+        create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+
+        assert_ne!(read_file(out_dir.join("helpers.mjs")), "marker");
+    }
+
+    #[tokio::test]
+    async fn removes_outputs_of_deleted_files() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        assert!(out_dir.join("helpers.mjs").exists());
+
+        fs::remove_file(sandbox.path().join("src/helpers.js")).unwrap();
+
+        compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        assert!(!out_dir.join("helpers.mjs").exists());
+        assert!(!read_file(sandbox.path().join(".espm/cache.es2018.json")).contains("helpers.js"));
+    }
+
+    #[test]
+    fn hashes_resolved_typescript_version() {
+        let sandbox = create_sandbox("ts-files");
+        let settings = ManifestBuild::default();
+        let plugins = CompilerPlugins::default();
+
+        let hash = |version: &str| {
+            hash_settings(
+                &settings,
+                &EsTarget::Es2018,
+                sandbox.path(),
+                &plugins,
+                Some(&Version::parse(version).unwrap()),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(hash("5.2.2"), hash("5.2.2"));
+        assert_ne!(hash("5.2.2"), hash("5.4.5"));
+    }
+
+    #[test]
+    fn hashes_dependency_declarations() {
+        let sandbox = create_empty_sandbox();
+        let out_dirs = vec![sandbox.path().join("packages/foo/.espm/es2018")];
+        let hash = || hash_dependencies(&out_dirs).unwrap();
+
+        let unbuilt = hash();
+
+        sandbox.create_file("packages/foo/.espm/es2018/index.mjs", "export {};");
+
+        assert_eq!(hash(), unbuilt);
+
+        sandbox.create_file("packages/foo/.espm/es2018/index.d.mts", "export {};");
+
+        let built = hash();
+
+        assert_ne!(built, unbuilt);
+
+        sandbox.create_file(
+            "packages/foo/.espm/es2018/index.d.mts",
+            "export declare const a: string;",
+        );
+
+        assert_ne!(hash(), built);
+    }
+}
+
+mod build_report {
+    use super::*;
+
+    #[tokio::test]
+    async fn writes_report_for_target() {
+        let sandbox = create_sandbox("ts-files");
+        let package = Package::new(sandbox.path()).unwrap();
+
+        let reports = create_compiler(sandbox.path(), &package)
+            .compile_targets(&[EsTarget::Es2018])
+            .await
+            .unwrap();
+        let report = &reports[0];
+
+        assert!(report.out_dir.join("build.json").exists());
+        assert!(report.timings.declarations.is_some());
+        assert!(!report
+            .files
+            .iter()
+            .any(|file| file.path.as_str() == "build.json"));
+
+        let file = report
+            .files
+            .iter()
+            .find(|file| file.path.as_str() == "index.mjs")
+            .unwrap();
+
+        assert_eq!(file.source.as_ref().unwrap().as_str(), "index.ts");
+        assert!(file.size > 0);
+        assert!(file.gzip_size > 0);
+        assert!(file.brotli_size > 0);
+        assert_eq!(file.hash.len(), 64);
+
+        let file = report
+            .files
+            .iter()
+            .find(|file| file.path.as_str() == "helpers.d.mts")
+            .unwrap();
+
+        assert_eq!(file.source.as_ref().unwrap().as_str(), "helpers.ts");
+
+        let json = read_file(report.out_dir.join("build.json"));
+
+        assert!(json.contains("\"target\": \"es2018\""));
+        assert!(json.contains("\"gzipSize\""));
+        assert!(json.contains("\"settings\""));
+    }
+
+    #[tokio::test]
+    async fn maps_assets_to_their_source() {
+        let sandbox = create_sandbox("assets");
+        let package = Package::new(sandbox.path()).unwrap();
+
+        let reports = create_compiler(sandbox.path(), &package)
+            .compile_targets(&[EsTarget::Es2018])
+            .await
+            .unwrap();
+
+        let file = reports[0]
+            .files
+            .iter()
+            .find(|file| file.path.as_str() == "moon.svg")
+            .unwrap();
+
+        assert_eq!(file.source.as_ref().unwrap().as_str(), "moon.svg");
+    }
+}
+
+mod compile_files {
+    use super::*;
+
+    #[tokio::test]
+    async fn recompiles_changed_modules() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+        let helpers = read_file(out_dir.join("helpers.mjs"));
+
+        sandbox.create_file("src/index.js", "export const changed = true;\n");
+        sandbox.create_file("src/new.js", "export const added = true;\n");
+
+        compiler
+            .compile_files(
+                EsTarget::Es2018,
+                &[
+                    sandbox.path().join("src/index.js"),
+                    sandbox.path().join("src/new.js"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert!(read_file(out_dir.join("index.mjs")).contains("changed"));
+        assert!(read_file(out_dir.join("new.mjs")).contains("added"));
+        assert_eq!(read_file(out_dir.join("helpers.mjs")), helpers);
+    }
+
+    #[tokio::test]
+    async fn copies_changed_assets() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        sandbox.create_file("src/styles.css", "body {}");
+
+        compiler
+            .compile_files(EsTarget::Es2018, &[sandbox.path().join("src/styles.css")])
+            .await
+            .unwrap();
+
+        assert_eq!(read_file(out_dir.join("styles.css")), "body {}");
+    }
+
+    #[tokio::test]
+    async fn removes_output_of_deleted_files() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file("src/styles.css", "body {}");
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        assert!(out_dir.join("helpers.mjs").exists());
+        assert!(out_dir.join("styles.css").exists());
+
+        fs::remove_file(sandbox.path().join("src/helpers.js")).unwrap();
+        fs::remove_file(sandbox.path().join("src/styles.css")).unwrap();
+
+        compiler
+            .compile_files(
+                EsTarget::Es2018,
+                &[
+                    sandbox.path().join("src/helpers.js"),
+                    sandbox.path().join("src/styles.css"),
+                ],
+            )
+            .await
+            .unwrap();
+
+        assert!(!out_dir.join("helpers.mjs").exists());
+        assert!(!out_dir.join("styles.css").exists());
+        assert!(out_dir.join("index.mjs").exists());
+    }
+
+    #[tokio::test]
+    async fn updates_the_build_report() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        sandbox.create_file("src/styles.css", "body {}");
+
+        let report = compiler
+            .compile_files(EsTarget::Es2018, &[sandbox.path().join("src/styles.css")])
+            .await
+            .unwrap();
+
+        assert!(report
+            .files
+            .iter()
+            .any(|file| file.path.as_str() == "styles.css"));
+        assert!(read_file(report.out_dir.join("build.json")).contains("styles.css"));
+    }
+}
+
+mod jsx {
+    use super::*;
+    use espresso_manifest::BuildJsxRuntime;
+
+    async fn compile_jsx(package: &Package, sandbox: &starbase_sandbox::Sandbox) -> String {
+        let out_dir = create_compiler(sandbox.path(), package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+
+        read_file(out_dir.join("index.mjs"))
+    }
+
+    #[tokio::test]
+    async fn uses_automatic_runtime_by_default() {
+        let sandbox = create_sandbox("jsx");
+        let package = Package::new(sandbox.path()).unwrap();
+        let output = compile_jsx(&package, &sandbox).await;
+
+        assert!(output.contains("\"react/jsx-runtime\""));
+        assert!(!output.contains("<div"));
+    }
+
+    #[tokio::test]
+    async fn can_change_import_source() {
+        let sandbox = create_sandbox("jsx");
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        package.manifest.build.jsx.import_source = Some("preact".into());
+
+        let output = compile_jsx(&package, &sandbox).await;
+
+        assert!(output.contains("\"preact/jsx-runtime\""));
+    }
+
+    #[tokio::test]
+    async fn can_enable_development() {
+        let sandbox = create_sandbox("jsx");
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        package.manifest.build.jsx.development = true;
+
+        let output = compile_jsx(&package, &sandbox).await;
+
+        assert!(output.contains("\"react/jsx-dev-runtime\""));
+    }
+
+    #[tokio::test]
+    async fn supports_classic_runtime_with_pragma() {
+        let sandbox = create_sandbox("jsx");
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        package.manifest.build.jsx.runtime = BuildJsxRuntime::Classic;
+        package.manifest.build.jsx.pragma = Some("h".into());
+        package.manifest.build.jsx.pragma_frag = Some("Fragment".into());
+
+        let output = compile_jsx(&package, &sandbox).await;
+
+        assert!(output.contains("h(Fragment"));
+        assert!(output.contains("h(\"div\""));
+        assert!(!output.contains("jsx-runtime"));
+    }
+}
+
+mod decorators {
+    use super::*;
+
+    #[tokio::test]
+    async fn compiles_standard_decorators() {
+        let sandbox = create_sandbox("syntax-standard-decorators");
+        let package = Package::new(sandbox.path()).unwrap();
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2022)
+            .await
+            .unwrap();
+
+        for file in ["index.mjs", "other.mjs"] {
+            let output = read_file(out_dir.join(file));
+
+            assert!(output.contains("_apply_decs_2203"));
+            assert!(!output.contains("_ts_decorate"));
+            assert!(!output.contains("_ts_metadata"));
+        }
+
+        assert!(read_file(out_dir.join("../tsconfig.es2022.json"))
+            .contains("\"experimentalDecorators\": false"));
+    }
+}
+
+mod latest_targets {
+    use super::*;
+
+    #[tokio::test]
+    async fn preserves_hashbangs() {
+        let sandbox = create_sandbox("syntax-es2023");
+        let package = Package::new(sandbox.path()).unwrap();
+
+        for target in [EsTarget::Es2023, EsTarget::Es2024] {
+            let out_dir = create_compiler(sandbox.path(), &package)
+                .compile(target)
+                .await
+                .unwrap();
+
+            assert!(read_file(out_dir.join("index.mjs")).starts_with("#!/usr/bin/env node"));
+        }
+    }
+
+    #[tokio::test]
+    async fn includes_array_find_last_typings() {
+        let sandbox = create_sandbox("syntax-es2023");
+        let package = Package::new(sandbox.path()).unwrap();
+
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2023)
+            .await
+            .unwrap();
+        let dts = read_file(out_dir.join("index.d.mts"));
+
+        assert!(dts.contains("last: number | undefined"));
+        assert!(dts.contains("lastIndex: number"));
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_esnext_for_older_typescript() {
+        let sandbox = create_sandbox("syntax-es2023");
+
+        let decl = Declarations::new(
+            sandbox.path().to_path_buf(),
+            sandbox.path().join(".espm"),
+            Arc::new(ManifestBuild::default()),
+            Arc::new(Store::load_from(sandbox.path()).unwrap()),
+        );
+
+        for (target, ts_version, expected) in [
+            (EsTarget::Es2023, "4.9.5", "esnext"),
+            (EsTarget::Es2023, "5.2.2", "es2023"),
+            (EsTarget::Es2024, "5.2.2", "esnext"),
+            (EsTarget::Es2024, "5.7.2", "es2024"),
+        ] {
+            let state = decl
+                .create_tsconfig(&target, &Version::parse(ts_version).unwrap())
+                .unwrap();
+            let tsconfig: serde_json::Value = serde_json::from_str(&read_file(state.path)).unwrap();
+
+            assert_eq!(tsconfig["compilerOptions"]["target"], expected);
+            assert_eq!(tsconfig["compilerOptions"]["module"], "es2022");
+            assert_eq!(
+                tsconfig["compilerOptions"]["lib"],
+                serde_json::json!(["dom", expected, "esnext.asynciterable"])
+            );
+        }
+    }
+}
+
+mod minify {
+    use super::*;
+    use espresso_manifest::{BuildMinify, BuildMinifyOptions};
+
+    #[tokio::test]
+    async fn doesnt_minify_by_default() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file("src/comment.js", "// Comment\nexport const value = 123;\n");
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+
+        assert!(read_file(out_dir.join("comment.mjs")).contains("// Comment"));
+    }
+
+    #[tokio::test]
+    async fn minifies_modules() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file("src/comment.js", "// Comment\nexport const value = 123;\n");
+
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        let base_size = fs::metadata(
+            create_compiler(sandbox.path(), &package)
+                .compile(EsTarget::Es2018)
+                .await
+                .unwrap()
+                .join("index.mjs"),
+        )
+        .unwrap()
+        .len();
+
+        package.manifest.build.minify = BuildMinify::Enabled(true);
+
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+
+        assert!(fs::metadata(out_dir.join("index.mjs")).unwrap().len() < base_size);
+        assert!(!read_file(out_dir.join("comment.mjs")).contains("// Comment"));
+    }
+
+    #[tokio::test]
+    async fn keeps_license_comments() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file(
+            "src/comment.js",
+            "/*! Copyright ns */\n/** @license MIT */\n// Comment\nexport const value = 123;\n",
+        );
+
+        let mut package = Package::new(sandbox.path()).unwrap();
+        package.manifest.build.minify = BuildMinify::Enabled(true);
+
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+        let output = read_file(out_dir.join("comment.mjs"));
+
+        assert!(output.contains("Copyright ns"));
+        assert!(output.contains("@license MIT"));
+        assert!(!output.contains("// Comment"));
+    }
+
+    #[tokio::test]
+    async fn can_keep_class_names() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file(
+            "src/class.js",
+            "class Internal {}\nexport function create() { return new Internal(); }\n",
+        );
+
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        package.manifest.build.minify = BuildMinify::Options(BuildMinifyOptions {
+            compress: false,
+            mangle: true,
+            keep_class_names: true,
+        });
+
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+
+        assert!(read_file(out_dir.join("class.mjs")).contains("Internal"));
+    }
+}
+
+mod path_aliases {
+    use super::*;
+
+    #[tokio::test]
+    async fn resolves_paths_to_relative_specifiers() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file(
+            "tsconfig.espm.json",
+            r#"{ "compilerOptions": { "paths": { "@/*": ["./src/*"] } } }"#,
+        );
+        sandbox.create_file(
+            "src/nested/file.js",
+            "import { a } from '@/helpers';\nexport * from '@/index';\nexport const b = a;\n",
+        );
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+        let output = read_file(out_dir.join("nested/file.mjs"));
+
+        assert!(output.contains("\"../helpers.mjs\""));
+        assert!(output.contains("\"../index.mjs\""));
+        assert!(!output.contains("@/"));
+    }
+
+    #[tokio::test]
+    async fn resolves_exact_paths_and_directories() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file(
+            "tsconfig.espm.json",
+            r#"{ "compilerOptions": { "paths": { "~utils": ["./src/utils"] } } }"#,
+        );
+        sandbox.create_file("src/utils/index.js", "export const a = 1;\n");
+        sandbox.create_file("src/file.js", "export * from '~utils';\n");
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+
+        assert!(read_file(out_dir.join("file.mjs")).contains("\"./utils/index.mjs\""));
+    }
+
+    #[tokio::test]
+    async fn resolves_from_base_url() {
+        let sandbox = create_sandbox("js-files");
+        sandbox.create_file(
+            "tsconfig.espm.json",
+            r#"{ "compilerOptions": { "baseUrl": "src" } }"#,
+        );
+        sandbox.create_file(
+            "src/file.js",
+            "export * from 'helpers';\nexport * from 'react';\n",
+        );
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+        let output = read_file(out_dir.join("file.mjs"));
+
+        assert!(output.contains("\"./helpers.mjs\""));
+        // Not a file, so left as-is
+        assert!(output.contains("'react'"));
+    }
+
+    #[tokio::test]
+    async fn resolves_aliases_in_declarations() {
+        let sandbox = create_sandbox("ts-files");
+        sandbox.create_file(
+            "tsconfig.espm.json",
+            r#"{ "compilerOptions": { "paths": { "@/*": ["./src/*"] } } }"#,
+        );
+        sandbox.create_file(
+            "src/nested/file.ts",
+            "export * from '@/helpers';\nexport type T = import('@/index').Foo;\n",
+        );
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let out_dir = create_compiler(sandbox.path(), &package)
+            .compile(EsTarget::Es2018)
+            .await
+            .unwrap();
+        let module = read_file(out_dir.join("nested/file.mjs"));
+        let decl = read_file(out_dir.join("nested/file.d.mts"));
+
+        assert!(module.contains("\"../helpers.mjs\""));
+        assert!(decl.contains("\"../helpers.mjs\""));
+        assert!(decl.contains("\"../index.mjs\""));
+        assert!(!decl.contains("@/"));
+    }
 }
 
 mod compile_assets {
@@ -120,6 +819,37 @@ mod compile_declarations {
         assert!(!out_dir.join("helpers.d.ts").exists());
     }
 
+    #[tokio::test]
+    async fn copies_dmts_to_other_targets() {
+        let sandbox = create_sandbox("ts-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let reports = compiler
+            .compile_targets(&[EsTarget::Es2015, EsTarget::Es2022])
+            .await
+            .unwrap();
+
+        assert_eq!(reports.len(), 2);
+
+        // tsc is only ran for the first target
+        assert!(sandbox.path().join(".espm/tsconfig.es2015.json").exists());
+        assert!(!sandbox.path().join(".espm/tsconfig.es2022.json").exists());
+
+        for report in reports {
+            let out_dir = report.out_dir;
+
+            assert!(out_dir.join("index.mjs").exists());
+            assert!(out_dir.join("index.d.mts").exists());
+            assert!(out_dir.join("helpers.d.mts").exists());
+        }
+
+        assert_eq!(
+            read_file(sandbox.path().join(".espm/es2015/index.d.mts")),
+            read_file(sandbox.path().join(".espm/es2022/index.d.mts"))
+        );
+    }
+
     #[tokio::test]
     async fn doesnt_generate_dmts_for_js_files() {
         let sandbox = create_sandbox("js-files");
@@ -153,7 +883,9 @@ mod compile_declarations {
 }"#,
         );
 
-        let state = decl.create_tsconfig(&EsTarget::Es2022).unwrap();
+        let state = decl
+            .create_tsconfig(&EsTarget::Es2022, &Version::parse(TS_VERSION).unwrap())
+            .unwrap();
 
         assert_snapshot!(read_file(state.path));
         assert!(!state.project_references);
@@ -174,7 +906,7 @@ mod compile_declarations {
             "tsconfig.espm.json",
             r#"{
     "exclude": ["src/*.test.*", "tests/**/*"],
-    "extends": ["../../tsconfig.options.json", "npm-package/tsconfig.json"],
+    "extends": "./configs/tsconfig.options.json",
     "compilerOptions": {
         "baseUrl": "src",
         "rootDirs": [".", "../types"],
@@ -190,9 +922,504 @@ mod compile_declarations {
 }"#,
         );
 
-        let state = decl.create_tsconfig(&EsTarget::Es2022).unwrap();
+        sandbox.create_file(
+            "configs/tsconfig.options.json",
+            r#"{
+    "compilerOptions": {
+        "strict": true,
+        "typeRoots": ["../types"]
+    }
+}"#,
+        );
+
+        let state = decl
+            .create_tsconfig(&EsTarget::Es2022, &Version::parse(TS_VERSION).unwrap())
+            .unwrap();
 
         assert_snapshot!(read_file(state.path));
         assert!(state.project_references);
     }
+
+    #[tokio::test]
+    async fn creates_a_tsconfig_for_checking() {
+        let sandbox = create_sandbox("ts-files");
+
+        let decl = Declarations::new(
+            sandbox.path().to_path_buf(),
+            sandbox.path().join(".espm"),
+            Arc::new(ManifestBuild::default()),
+            Arc::new(Store::load_from(sandbox.path()).unwrap()),
+        );
+
+        let state = decl
+            .create_check_tsconfig(&EsTarget::Es2022, &Version::parse(TS_VERSION).unwrap())
+            .unwrap();
+        let tsconfig: serde_json::Value = serde_json::from_str(&read_file(&state.path)).unwrap();
+
+        assert!(state.path.ends_with(".espm/tsconfig.check.json"));
+        assert_eq!(
+            tsconfig["include"],
+            serde_json::json!(["../src/**/*", "../tests/**/*"])
+        );
+        assert_eq!(tsconfig["compilerOptions"]["noEmit"], true);
+        assert!(tsconfig["compilerOptions"]
+            .get("emitDeclarationOnly")
+            .is_none());
+        assert!(tsconfig["compilerOptions"].get("rootDir").is_none());
+        assert!(!state.project_references);
+    }
+
+    #[tokio::test]
+    async fn references_workspace_dependencies() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("app/src/index.ts", "export {};");
+        sandbox.create_file("lib/src/index.ts", "export {};");
+
+        let app_name = PackageName::parse("ns/app").unwrap();
+        let lib_name = PackageName::parse("ns/lib").unwrap();
+        let mut workspace_packages = WorkspacePackages::new();
+
+        workspace_packages.insert(
+            app_name.clone(),
+            WorkspacePackage {
+                build_settings: Arc::new(ManifestBuild::default()),
+                dependencies: vec![lib_name.clone()],
+                root: sandbox.path().join("app"),
+            },
+        );
+
+        workspace_packages.insert(
+            lib_name.clone(),
+            WorkspacePackage {
+                build_settings: Arc::new(ManifestBuild::default()),
+                dependencies: vec![],
+                root: sandbox.path().join("lib"),
+            },
+        );
+
+        let mut decl = Declarations::new(
+            sandbox.path().join("app"),
+            sandbox.path().join("app/.espm/es2022"),
+            Arc::new(ManifestBuild::default()),
+            Arc::new(Store::load_from(sandbox.path()).unwrap()),
+        );
+        decl.dependencies = vec![lib_name];
+        decl.workspace_packages = Arc::new(workspace_packages);
+
+        let state = decl
+            .create_tsconfig(&EsTarget::Es2022, &Version::parse(TS_VERSION).unwrap())
+            .unwrap();
+        let tsconfig: serde_json::Value = serde_json::from_str(&read_file(&state.path)).unwrap();
+
+        assert!(state.project_references);
+        assert_eq!(
+            tsconfig["references"],
+            serde_json::json!([{ "path": "./references/es2022/ns/lib/tsconfig.json" }])
+        );
+        assert_eq!(tsconfig["compilerOptions"]["composite"], true);
+        assert_eq!(
+            tsconfig["compilerOptions"]["tsBuildInfoFile"],
+            "./tsconfig.es2022.tsbuildinfo"
+        );
+        assert_eq!(
+            tsconfig["compilerOptions"]["paths"],
+            serde_json::json!({
+                "ns/lib": ["../../lib/src"],
+                "ns/lib/*": ["../../lib/src/*"],
+            })
+        );
+
+        // Dependencies are built into a directory private to the package
+        let lib_tsconfig: serde_json::Value = serde_json::from_str(&read_file(
+            sandbox
+                .path()
+                .join("app/.espm/references/es2022/ns/lib/tsconfig.json"),
+        ))
+        .unwrap();
+
+        assert_eq!(
+            lib_tsconfig["include"],
+            serde_json::json!(["../../../../../../lib/src/**/*"])
+        );
+        assert_eq!(lib_tsconfig["compilerOptions"]["composite"], true);
+        assert_eq!(lib_tsconfig["compilerOptions"]["outDir"], ".");
+        assert_eq!(
+            lib_tsconfig["compilerOptions"]["rootDir"],
+            "../../../../../../lib/src"
+        );
+        assert_eq!(
+            lib_tsconfig["compilerOptions"]["tsBuildInfoFile"],
+            "./tsconfig.tsbuildinfo"
+        );
+        assert!(lib_tsconfig.get("references").is_none());
+        assert!(!sandbox.path().join("lib/.espm").exists());
+    }
+
+    #[tokio::test]
+    async fn references_transitive_workspace_dependencies() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("app/src/index.ts", "export {};");
+        sandbox.create_file("lib/src/index.ts", "export {};");
+        sandbox.create_file("utils/src/index.ts", "export {};");
+
+        let lib_name = PackageName::parse("ns/lib").unwrap();
+        let utils_name = PackageName::parse("ns/utils").unwrap();
+        let mut workspace_packages = WorkspacePackages::new();
+
+        workspace_packages.insert(
+            lib_name.clone(),
+            WorkspacePackage {
+                build_settings: Arc::new(ManifestBuild::default()),
+                dependencies: vec![utils_name.clone()],
+                root: sandbox.path().join("lib"),
+            },
+        );
+
+        workspace_packages.insert(
+            utils_name.clone(),
+            WorkspacePackage {
+                build_settings: Arc::new(ManifestBuild::default()),
+                dependencies: vec![],
+                root: sandbox.path().join("utils"),
+            },
+        );
+
+        let mut decl = Declarations::new(
+            sandbox.path().join("app"),
+            sandbox.path().join("app/.espm/es2022"),
+            Arc::new(ManifestBuild::default()),
+            Arc::new(Store::load_from(sandbox.path()).unwrap()),
+        );
+        decl.dependencies = vec![lib_name];
+        decl.workspace_packages = Arc::new(workspace_packages);
+
+        decl.create_tsconfig(&EsTarget::Es2022, &Version::parse(TS_VERSION).unwrap())
+            .unwrap();
+
+        let references_dir = sandbox.path().join("app/.espm/references/es2022");
+        let lib_tsconfig: serde_json::Value =
+            serde_json::from_str(&read_file(references_dir.join("ns/lib/tsconfig.json"))).unwrap();
+
+        assert_eq!(
+            lib_tsconfig["references"],
+            serde_json::json!([{ "path": "../utils/tsconfig.json" }])
+        );
+        assert_eq!(
+            lib_tsconfig["compilerOptions"]["paths"],
+            serde_json::json!({
+                "ns/utils": ["../../../../../../utils/src"],
+                "ns/utils/*": ["../../../../../../utils/src/*"],
+            })
+        );
+        assert!(references_dir.join("ns/utils/tsconfig.json").exists());
+    }
+
+    #[tokio::test]
+    async fn creates_an_editor_tsconfig() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("app/src/index.ts", "export {};");
+        sandbox.create_file("lib/src/index.ts", "export {};");
+
+        let lib_name = PackageName::parse("ns/lib").unwrap();
+        let mut workspace_packages = WorkspacePackages::new();
+
+        workspace_packages.insert(
+            lib_name.clone(),
+            WorkspacePackage {
+                build_settings: Arc::new(ManifestBuild::default()),
+                dependencies: vec![],
+                root: sandbox.path().join("lib"),
+            },
+        );
+
+        let mut decl = Declarations::new(
+            sandbox.path().join("app"),
+            sandbox.path().join("app/.espm/es2022"),
+            Arc::new(ManifestBuild::default()),
+            Arc::new(Store::load_from(sandbox.path()).unwrap()),
+        );
+        decl.dependencies = vec![lib_name];
+        decl.workspace_packages = Arc::new(workspace_packages);
+
+        let state = decl
+            .create_editor_tsconfig(&EsTarget::Es2022, &Version::parse(TS_VERSION).unwrap())
+            .unwrap();
+        let contents = read_file(&state.path);
+        let tsconfig: serde_json::Value = serde_json::from_str(&strip_jsonc(&contents)).unwrap();
+
+        assert_eq!(state.path, sandbox.path().join("app/tsconfig.json"));
+        assert!(contents.starts_with(EDITOR_TSCONFIG_HEADER));
+        assert!(is_generated_tsconfig(&state.path).unwrap());
+        assert_eq!(
+            tsconfig["include"],
+            serde_json::json!(["./src/**/*", "./tests/**/*"])
+        );
+        assert_eq!(
+            tsconfig["references"],
+            serde_json::json!([{ "path": "../lib/tsconfig.json" }])
+        );
+        assert_eq!(
+            tsconfig["compilerOptions"]["paths"],
+            serde_json::json!({
+                "ns/lib": ["../lib/src"],
+                "ns/lib/*": ["../lib/src/*"],
+            })
+        );
+        assert_eq!(tsconfig["compilerOptions"]["target"], "es2022");
+        assert_eq!(tsconfig["compilerOptions"]["outDir"], "./.espm/types");
+        assert!(tsconfig["compilerOptions"].get("rootDir").is_none());
+    }
+
+    #[test]
+    fn creates_a_solution_tsconfig() {
+        let sandbox = create_empty_sandbox();
+
+        let path = create_solution_tsconfig(
+            sandbox.path(),
+            &[
+                &sandbox.path().join("packages/a"),
+                &sandbox.path().join("packages/b"),
+            ],
+        )
+        .unwrap();
+        let tsconfig: serde_json::Value =
+            serde_json::from_str(&strip_jsonc(&read_file(&path))).unwrap();
+
+        assert!(is_generated_tsconfig(&path).unwrap());
+        assert_eq!(
+            tsconfig,
+            serde_json::json!({
+                "files": [],
+                "references": [
+                    { "path": "./packages/a/tsconfig.json" },
+                    { "path": "./packages/b/tsconfig.json" },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn detects_tsconfigs_not_generated() {
+        let sandbox = create_empty_sandbox();
+        let path = sandbox.path().join("tsconfig.json");
+
+        assert!(is_generated_tsconfig(&path).unwrap());
+
+        sandbox.create_file("tsconfig.json", "{}");
+
+        assert!(!is_generated_tsconfig(&path).unwrap());
+    }
+
+    #[tokio::test]
+    async fn uses_default_typescript_version() {
+        let sandbox = create_sandbox("ts-files");
+
+        let decl = Declarations::new(
+            sandbox.path().to_path_buf(),
+            sandbox.path().join(".espm"),
+            Arc::new(ManifestBuild::default()),
+            Arc::new(Store::load_from(sandbox.path()).unwrap()),
+        );
+
+        assert_eq!(
+            decl.resolve_typescript_version().await.unwrap(),
+            Version::parse(TS_VERSION).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn omits_newer_options_for_older_typescript() {
+        let sandbox = create_sandbox("ts-files");
+
+        let decl = Declarations::new(
+            sandbox.path().to_path_buf(),
+            sandbox.path().join(".espm"),
+            Arc::new(ManifestBuild::default()),
+            Arc::new(Store::load_from(sandbox.path()).unwrap()),
+        );
+
+        let state = decl
+            .create_tsconfig(&EsTarget::Es2022, &Version::parse("4.9.5").unwrap())
+            .unwrap();
+        let tsconfig = read_file(state.path);
+
+        assert!(!tsconfig.contains("allowArbitraryExtensions"));
+        assert!(!tsconfig.contains("allowImportingTsExtensions"));
+        assert!(!tsconfig.contains("resolvePackageJsonExports"));
+        assert!(tsconfig.contains(r#""moduleResolution": "node""#));
+    }
+}
+
+mod source_maps {
+    use super::*;
+    use base64::Engine;
+    use espresso_manifest::BuildSourceMaps;
+
+    #[tokio::test]
+    async fn doesnt_create_maps_by_default() {
+        let sandbox = create_sandbox("js-files");
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        assert!(!out_dir.join("index.mjs.map").exists());
+        assert!(!read_file(out_dir.join("index.mjs")).contains("sourceMappingURL"));
+    }
+
+    #[tokio::test]
+    async fn creates_external_maps() {
+        let sandbox = create_sandbox("ts-files");
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        package.manifest.build.source_maps = BuildSourceMaps::External;
+
+        let compiler = create_compiler(sandbox.path(), &package);
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+        let code = read_file(out_dir.join("index.mjs"));
+        let map =
+            sourcemap::SourceMap::from_slice(&fs::read(out_dir.join("index.mjs.map")).unwrap())
+                .unwrap();
+
+        assert!(code.contains("//# sourceMappingURL=index.mjs.map"));
+        assert_eq!(map.get_file(), Some("index.mjs"));
+        assert_eq!(map.get_source(0), Some("../../src/index.ts"));
+
+        // The rewritten `.mjs` specifier still maps to the original export
+        let line = code
+            .lines()
+            .position(|line| line.contains("./helpers.mjs"))
+            .unwrap();
+        let token = map.lookup_token(line as u32, 0).unwrap();
+
+        assert_eq!(token.get_src_line(), 0);
+    }
+
+    #[tokio::test]
+    async fn creates_inline_maps() {
+        let sandbox = create_sandbox("js-files");
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        package.manifest.build.source_maps = BuildSourceMaps::Inline;
+
+        let compiler = create_compiler(sandbox.path(), &package);
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        let code = read_file(out_dir.join("index.mjs"));
+        let (_, data) = code
+            .split_once("//# sourceMappingURL=data:application/json;base64,")
+            .unwrap();
+        let map = sourcemap::SourceMap::from_slice(
+            &base64::engine::general_purpose::STANDARD
+                .decode(data.trim())
+                .unwrap(),
+        )
+        .unwrap();
+
+        assert!(!out_dir.join("index.mjs.map").exists());
+        assert_eq!(map.get_file(), Some("index.mjs"));
+        assert_eq!(map.get_source(0), Some("../../src/index.js"));
+    }
+
+    #[tokio::test]
+    async fn creates_declaration_maps() {
+        let sandbox = create_sandbox("ts-files");
+        let mut package = Package::new(sandbox.path()).unwrap();
+
+        package.manifest.build.source_maps = BuildSourceMaps::External;
+
+        let compiler = create_compiler(sandbox.path(), &package);
+        let out_dir = compiler.compile(EsTarget::Es2018).await.unwrap();
+
+        assert!(out_dir.join("index.d.mts.map").exists());
+        assert!(!out_dir.join("index.d.ts.map").exists());
+        assert!(
+            read_file(out_dir.join("index.d.mts")).contains("//# sourceMappingURL=index.d.mts.map")
+        );
+        assert!(read_file(out_dir.join("index.d.mts.map")).contains("\"file\":\"index.d.mts\""));
+        assert!(read_file(out_dir.join("index.d.mts.map")).contains("../src/index.ts"));
+    }
+}
+
+mod tsc_diagnostics {
+    use super::*;
+    use espresso_compiler::{parse_tsc_output, TscDiagnostic, TscFileDiagnostics};
+    use miette::Diagnostic;
+
+    const OUTPUT: &str = r#"src/index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.
+src/helpers.ts(1,10): error TS2305: Module '"./missing"' has no exported member 'a'.
+src/index.ts(1,1): error TS2345: Argument of type '{ a: string; }' is not assignable to parameter of type 'Options'.
+  Object literal may only specify known properties.
+error TS5023: Unknown compiler option 'foo'.
+"#;
+
+    #[test]
+    fn parses_output() {
+        let sandbox = create_sandbox("ts-files");
+        let diagnostics = parse_tsc_output(OUTPUT, sandbox.path());
+
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(
+            diagnostics[0],
+            TscDiagnostic {
+                file: Some("src/index.ts".into()),
+                line: 3,
+                column: 7,
+                category: "error".into(),
+                code: "TS2322".into(),
+                message: "Type 'string' is not assignable to type 'number'.".into(),
+            }
+        );
+        assert_eq!(
+            diagnostics[2].message,
+            "Argument of type '{ a: string; }' is not assignable to parameter of type 'Options'.\nObject literal may only specify known properties."
+        );
+        assert_eq!(diagnostics[3].file, None);
+        assert_eq!(diagnostics[3].code, "TS5023");
+    }
+
+    #[test]
+    fn relativizes_absolute_paths() {
+        let sandbox = create_sandbox("ts-files");
+        let output = format!(
+            "{}(1,1): error TS1005: ';' expected.",
+            sandbox.path().join("src/index.ts").display()
+        );
+        let diagnostics = parse_tsc_output(&output, sandbox.path());
+
+        assert_eq!(diagnostics[0].file, Some("src/index.ts".into()));
+    }
+
+    #[test]
+    fn ignores_other_output() {
+        let sandbox = create_sandbox("ts-files");
+
+        assert!(parse_tsc_output("Version 5.2.2\nFound 0 errors.", sandbox.path()).is_empty());
+    }
+
+    #[test]
+    fn groups_by_file_with_labels() {
+        let sandbox = create_sandbox("ts-files");
+        sandbox.create_file(
+            "src/index.ts",
+            "export {};\n\nconst value: number = 'abc';\n",
+        );
+
+        let groups =
+            TscFileDiagnostics::group(parse_tsc_output(OUTPUT, sandbox.path()), sandbox.path());
+
+        // Project diagnostics are sorted first
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].file, None);
+        assert!(groups[0].labels().is_none());
+        assert_eq!(groups[2].file, Some("src/index.ts".into()));
+        assert_eq!(groups[2].diagnostics.len(), 2);
+
+        let labels = groups[2].labels().unwrap().collect::<Vec<_>>();
+
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[1].offset(), 18);
+        assert_eq!(labels[1].len(), 5);
+        assert!(labels[1].label().unwrap().starts_with("TS2322: "));
+    }
 }
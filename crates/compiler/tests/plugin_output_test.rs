@@ -19,3 +19,206 @@ mod plugin_output {
         assert_snapshot!(read_file(out_dir.join("index.mjs")));
     }
 }
+
+mod compiler_plugins {
+    use super::*;
+    use espresso_compiler::{CompilerPlugin, PluginContext};
+    use starbase_sandbox::create_empty_sandbox;
+    use std::fs;
+    use swc_core::common::plugin::{
+        diagnostics::PluginCorePkgDiagnostics,
+        serialized::{PluginSerializedBytes, VersionedSerializable},
+        PLUGIN_TRANSFORM_AST_SCHEMA_VERSION,
+    };
+    use swc_core::common::DUMMY_SP;
+    use swc_core::ecma::{
+        ast::{Expr, ExprStmt, Lit, Module, ModuleItem, Program, Stmt, Str},
+        visit::VisitMut,
+    };
+
+    struct ReplaceStringVisitor {
+        from: &'static str,
+        to: &'static str,
+    }
+
+    impl VisitMut for ReplaceStringVisitor {
+        fn visit_mut_str(&mut self, n: &mut Str) {
+            if &*n.value == self.from {
+                n.value = self.to.into();
+                n.raw = None;
+            }
+        }
+    }
+
+    struct DefinePlugin;
+
+    impl CompilerPlugin for DefinePlugin {
+        fn name(&self) -> String {
+            "define@1.0.0".into()
+        }
+
+        fn before_transform(&self, _context: &PluginContext) -> Option<Box<dyn VisitMut>> {
+            Some(Box::new(ReplaceStringVisitor {
+                from: "__VERSION__",
+                to: "1.2.3",
+            }))
+        }
+
+        fn after_transform(&self, context: &PluginContext) -> Option<Box<dyn VisitMut>> {
+            Some(Box::new(ReplaceStringVisitor {
+                from: "__TARGET__",
+                to: if *context.target == EsTarget::Es2020 {
+                    "es2020"
+                } else {
+                    "other"
+                },
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_registered_plugins() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("esp.toml", "[package]\nname = \"ns/plugins\"");
+        sandbox.create_file(
+            "src/index.ts",
+            "export const version: string = '__VERSION__';\nexport const target = '__TARGET__';",
+        );
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let mut compiler = create_compiler(sandbox.path(), &package);
+        compiler.add_plugin(DefinePlugin);
+
+        let out_dir = compiler.compile(EsTarget::Es2020).await.unwrap();
+        let output = read_file(out_dir.join("index.mjs"));
+
+        assert!(output.contains(r#"version = "1.2.3""#));
+        assert!(output.contains(r#"target = "es2020""#));
+    }
+
+    // Plugins are usually compiled with swc's plugin SDK, which isn't possible here,
+    // so implement its ABI by hand, with a plugin that replaces every program
+    fn create_wasm_plugin(program: Program) -> Vec<u8> {
+        let diagnostics = PluginSerializedBytes::try_serialize(&VersionedSerializable::new(
+            PluginCorePkgDiagnostics {
+                pkg_version: "0.0.0".into(),
+                git_sha: String::new(),
+                cargo_features: String::new(),
+                ast_schema_version: PLUGIN_TRANSFORM_AST_SCHEMA_VERSION,
+            },
+        ))
+        .unwrap();
+        let program =
+            PluginSerializedBytes::try_serialize(&VersionedSerializable::new(program)).unwrap();
+
+        let escape = |bytes: &[u8]| {
+            bytes
+                .iter()
+                .map(|byte| format!("\\{byte:02x}"))
+                .collect::<String>()
+        };
+        let diagnostics_len = diagnostics.as_slice().len();
+        let program_ptr = 8 + diagnostics_len;
+        let program_len = program.as_slice().len();
+        let heap_ptr = program_ptr + program_len;
+
+        wat::parse_str(format!(
+            r#"(module
+  (import "env" "__set_transform_plugin_core_pkg_diagnostics" (func $set_diagnostics (param i32 i32)))
+  (import "env" "__set_transform_result" (func $set_result (param i32 i32)))
+  (memory (export "memory") 2)
+  (global $heap (mut i32) (i32.const {heap_ptr}))
+  (data (i32.const 8) "{}")
+  (data (i32.const {program_ptr}) "{}")
+  (func (export "__alloc") (param $size i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $heap))
+    (global.set $heap (i32.add (local.get $ptr) (local.get $size)))
+    (local.get $ptr))
+  (func (export "__free") (param i32 i32) (result i32)
+    (i32.const 0))
+  (func (export "__get_transform_plugin_core_pkg_diag") (result i32)
+    (call $set_diagnostics (i32.const 8) (i32.const {diagnostics_len}))
+    (i32.const 0))
+  (func (export "__transform_plugin_process_impl") (param i32 i32 i32 i32) (result i32)
+    (call $set_result (i32.const {program_ptr}) (i32.const {program_len}))
+    (i32.const 0)))"#,
+            escape(diagnostics.as_slice()),
+            escape(program.as_slice()),
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn runs_wasm_plugins() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "esp.toml",
+            "[package]\nname = \"ns/plugins\"\n\n[[build.plugins]]\npath = \"plugins/replace.wasm\"",
+        );
+        sandbox.create_file("src/index.js", "export const original = true;");
+
+        // `"replaced";`
+        let program = Program::Module(Module {
+            span: DUMMY_SP,
+            body: vec![ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+                span: DUMMY_SP,
+                expr: Box::new(Expr::Lit(Lit::Str(Str {
+                    span: DUMMY_SP,
+                    value: "replaced".into(),
+                    raw: None,
+                }))),
+            }))],
+            shebang: None,
+        });
+
+        fs::create_dir_all(sandbox.path().join("plugins")).unwrap();
+        fs::write(
+            sandbox.path().join("plugins/replace.wasm"),
+            create_wasm_plugin(program),
+        )
+        .unwrap();
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        let out_dir = compiler.compile(EsTarget::Es2020).await.unwrap();
+        let output = read_file(out_dir.join("index.mjs"));
+
+        assert!(output.contains(r#""replaced";"#));
+        assert!(!output.contains("original"));
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Failed to load swc plugin")]
+    async fn errors_for_invalid_wasm_plugin() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "esp.toml",
+            "[package]\nname = \"ns/plugins\"\n\n[[build.plugins]]\npath = \"plugins/invalid.wasm\"",
+        );
+        sandbox.create_file("plugins/invalid.wasm", "not wasm");
+        sandbox.create_file("src/index.js", "export {};");
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        compiler.compile(EsTarget::Es2020).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "Unable to find swc plugin")]
+    async fn errors_for_missing_wasm_plugin() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "esp.toml",
+            "[package]\nname = \"ns/plugins\"\n\n[[build.plugins]]\npath = \"plugins/missing.wasm\"",
+        );
+        sandbox.create_file("src/index.js", "export {};");
+
+        let package = Package::new(sandbox.path()).unwrap();
+        let compiler = create_compiler(sandbox.path(), &package);
+
+        compiler.compile(EsTarget::Es2020).await.unwrap();
+    }
+}
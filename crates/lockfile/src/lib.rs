@@ -1 +1,3 @@
-pub static LOCKFILE_NAME: &str = "espm.lock";
+mod lockfile;
+
+pub use lockfile::*;
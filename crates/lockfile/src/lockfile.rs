@@ -0,0 +1,50 @@
+use espresso_common::{PackageName, Version};
+use serde::{Deserialize, Serialize};
+use starbase_utils::toml;
+use std::path::Path;
+use tracing::debug;
+
+pub static LOCKFILE_NAME: &str = "espm.lock";
+
+/// A single resolved package (and version) within the lockfile.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct LockfilePackage {
+    pub name: PackageName,
+
+    pub version: Version,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencies: Vec<PackageName>,
+}
+
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Lockfile {
+    #[serde(default, rename = "package")]
+    pub packages: Vec<LockfilePackage>,
+}
+
+impl Lockfile {
+    pub fn load<P: AsRef<Path>>(path: P) -> miette::Result<Lockfile> {
+        let path = path.as_ref();
+        let path = if path.ends_with(LOCKFILE_NAME) {
+            path.to_path_buf()
+        } else {
+            path.join(LOCKFILE_NAME)
+        };
+
+        // Dependencies haven't been installed yet
+        if !path.exists() {
+            debug!(lockfile = ?path, "No lockfile found, using an empty lockfile");
+
+            return Ok(Lockfile::default());
+        }
+
+        debug!(lockfile = ?path, "Loading lockfile");
+
+        Ok(toml::read_file(path)?)
+    }
+}
@@ -0,0 +1,59 @@
+use espresso_common::{PackageName, Version};
+use espresso_lockfile::*;
+use starbase_sandbox::create_empty_sandbox;
+
+mod lockfile {
+    use super::*;
+
+    #[test]
+    fn returns_empty_if_missing() {
+        let sandbox = create_empty_sandbox();
+
+        assert_eq!(Lockfile::load(sandbox.path()).unwrap(), Lockfile::default());
+    }
+
+    #[test]
+    fn supports_placeholder_content() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(LOCKFILE_NAME, "# Coming soon!");
+
+        assert_eq!(Lockfile::load(sandbox.path()).unwrap(), Lockfile::default());
+    }
+
+    #[test]
+    fn loads_packages() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            LOCKFILE_NAME,
+            r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.2.3"
+checksum = "abc123"
+dependencies = ["ns/pkg-b"]
+
+[[package]]
+name = "ns/pkg-b"
+version = "0.1.0"
+"#,
+        );
+
+        assert_eq!(
+            Lockfile::load(sandbox.path()).unwrap().packages,
+            vec![
+                LockfilePackage {
+                    name: PackageName::parse("ns/pkg-a").unwrap(),
+                    version: Version::parse("1.2.3").unwrap(),
+                    checksum: Some("abc123".into()),
+                    dependencies: vec![PackageName::parse("ns/pkg-b").unwrap()],
+                },
+                LockfilePackage {
+                    name: PackageName::parse("ns/pkg-b").unwrap(),
+                    version: Version::parse("0.1.0").unwrap(),
+                    checksum: None,
+                    dependencies: vec![],
+                }
+            ]
+        );
+    }
+}
@@ -1,14 +1,189 @@
+use espresso_common::VersionReq;
 use relative_path::RelativePathBuf;
 use schematic::schema::IntegerKind;
 use schematic::{derive_enum, Config, ConfigEnum, SchemaType, Schematic, ValidateError};
+use serde::{Deserialize, Serialize};
 
 derive_enum!(
     #[derive(ConfigEnum)]
     pub enum BuildDecorators {
         Legacy,
+        Standard,
     }
 );
 
+derive_enum!(
+    #[derive(ConfigEnum, Copy, Default)]
+    pub enum BuildJsxRuntime {
+        #[default]
+        Automatic,
+        Classic,
+    }
+);
+
+#[derive(Config, Clone, Debug, Eq, PartialEq, Serialize)]
+#[config(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub struct BuildJsx {
+    /// Import JSX helpers automatically, or call the pragma function.
+    pub runtime: BuildJsxRuntime,
+
+    /// Package to import JSX helpers from when using the automatic runtime.
+    pub import_source: Option<String>,
+
+    /// Function to call for elements when using the classic runtime.
+    #[setting(validate = validate_pragma)]
+    pub pragma: Option<String>,
+
+    /// Component to use for fragments when using the classic runtime.
+    #[setting(validate = validate_pragma)]
+    pub pragma_frag: Option<String>,
+
+    /// Transform to development helpers, which include debug information.
+    pub development: bool,
+}
+
+fn validate_pragma<C>(
+    _value: &str,
+    partial: &PartialBuildJsx,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    if partial.runtime != Some(BuildJsxRuntime::Classic) {
+        return Err(ValidateError::new(
+            "pragmas are only supported by the classic runtime",
+        ));
+    }
+
+    Ok(())
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct BuildMinifyOptions {
+    /// Apply compression optimizations, like dead code removal.
+    pub compress: bool,
+
+    /// Shorten variable and function names.
+    pub mangle: bool,
+
+    /// Preserve the names of classes.
+    pub keep_class_names: bool,
+}
+
+impl Default for BuildMinifyOptions {
+    fn default() -> Self {
+        Self {
+            compress: true,
+            mangle: true,
+            keep_class_names: true,
+        }
+    }
+}
+
+derive_enum!(
+    #[serde(untagged, expecting = "a boolean or minify options")]
+    pub enum BuildMinify {
+        Enabled(bool),
+        Options(BuildMinifyOptions),
+    }
+);
+
+impl BuildMinify {
+    pub fn get_options(&self) -> Option<BuildMinifyOptions> {
+        match self {
+            Self::Enabled(true) => Some(BuildMinifyOptions::default()),
+            Self::Enabled(false) => None,
+            Self::Options(options) => Some(options.to_owned()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            Self::Enabled(enabled) => *enabled,
+            Self::Options(_) => true,
+        }
+    }
+}
+
+impl Default for BuildMinify {
+    fn default() -> Self {
+        Self::Enabled(false)
+    }
+}
+
+impl Schematic for BuildMinify {
+    fn generate_schema() -> SchemaType {
+        SchemaType::union([
+            SchemaType::boolean(),
+            SchemaType::object(SchemaType::string(), SchemaType::boolean()),
+        ])
+    }
+}
+
+/// A swc Wasm plugin that transforms every module, either from
+/// an npm package (by name), or a file (by path).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct BuildPlugin {
+    /// Name of an npm package, resolved from `node_modules`.
+    pub name: Option<String>,
+
+    /// Path to a `.wasm` file, relative from the package root.
+    pub path: Option<RelativePathBuf>,
+
+    /// Configuration passed to the plugin as JSON.
+    #[serde(default, skip_serializing_if = "serde_json::Value::is_null")]
+    pub config: serde_json::Value,
+}
+
+impl BuildPlugin {
+    pub fn get_label(&self) -> String {
+        match (&self.name, &self.path) {
+            (Some(name), _) => name.to_owned(),
+            (None, Some(path)) => path.to_string(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+impl Schematic for BuildPlugin {
+    fn generate_schema() -> SchemaType {
+        SchemaType::object(SchemaType::string(), SchemaType::Unknown)
+    }
+}
+
+fn validate_plugins<D, C>(
+    value: &[BuildPlugin],
+    _partial: &D,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    for plugin in value {
+        if plugin.name.is_some() == plugin.path.is_some() {
+            return Err(ValidateError::new(
+                "plugins require either a name or a path, but not both",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+derive_enum!(
+    #[derive(ConfigEnum, Copy, Default)]
+    pub enum BuildSourceMaps {
+        #[default]
+        Off,
+        External,
+        Inline,
+    }
+);
+
+impl BuildSourceMaps {
+    pub fn is_enabled(&self) -> bool {
+        !matches!(self, Self::Off)
+    }
+}
+
 derive_enum!(
     #[serde(untagged, expecting = "a boolean or compression level between 0-6")]
     pub enum BuildOptimizePng {
@@ -59,15 +234,31 @@ fn validate_png_level<D, C>(
     Ok(())
 }
 
-#[derive(Config, Clone, Debug, Eq, PartialEq)]
+#[derive(Config, Clone, Debug, PartialEq, Serialize)]
 #[config(rename_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
 pub struct ManifestBuild {
     pub decorators: Option<BuildDecorators>,
 
     pub exclude: Vec<RelativePathBuf>,
 
+    #[setting(nested)]
+    pub jsx: BuildJsx,
+
+    pub minify: BuildMinify,
+
     #[setting(validate = validate_png_level)]
     pub optimize_png: BuildOptimizePng,
+
+    /// swc Wasm plugins to transform modules with, in order.
+    #[setting(validate = validate_plugins)]
+    pub plugins: Vec<BuildPlugin>,
+
+    pub source_maps: BuildSourceMaps,
+
+    /// Version of TypeScript to generate declarations with,
+    /// resolved against the npm registry.
+    pub typescript_version: Option<VersionReq>,
 }
 
 impl ManifestBuild {
@@ -77,3 +268,10 @@ impl ManifestBuild {
             .is_some_and(|dec| dec == &BuildDecorators::Legacy)
     }
 }
+
+#[derive(Config, Debug, Eq, PartialEq)]
+#[config(rename_all = "kebab-case")]
+pub struct WorkspaceManifestBuild {
+    /// Version of TypeScript for packages that do not configure their own.
+    pub typescript_version: Option<VersionReq>,
+}
@@ -1,4 +1,5 @@
 use espresso_common::EsTarget;
+use relative_path::RelativePathBuf;
 use schematic::{derive_enum, Config, ConfigEnum};
 
 derive_enum!(
@@ -13,5 +14,12 @@ derive_enum!(
 #[config(rename_all = "kebab-case")]
 pub struct ManifestInstall {
     pub linker: InstallLinker,
+
+    /// Request the minified flavor of dependencies.
+    pub minify: bool,
+
     pub target: EsTarget,
+
+    /// Directory of vendored dependencies, relative from the workspace root.
+    pub vendor_dir: Option<RelativePathBuf>,
 }
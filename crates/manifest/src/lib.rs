@@ -1,6 +1,7 @@
 mod build_setting;
 mod common_settings;
 mod install_setting;
+mod license_setting;
 mod manifest_error;
 mod manifest_loader;
 mod package_manifest;
@@ -10,6 +11,7 @@ pub use build_setting::*;
 pub use common_settings::*;
 pub use espresso_common::EsTarget;
 pub use install_setting::*;
+pub use license_setting::*;
 pub use manifest_error::*;
 pub use manifest_loader::*;
 pub use package_manifest::*;
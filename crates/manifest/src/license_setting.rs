@@ -0,0 +1,52 @@
+use espresso_common::{LicenseType, Licensee};
+use schematic::{Config, ValidateError};
+
+#[derive(Config, Debug, Eq, PartialEq)]
+#[config(rename_all = "kebab-case")]
+pub struct ManifestLicenses {
+    /// Licenses that dependencies are allowed to use. When empty, all
+    /// licenses not explicitly denied are allowed.
+    #[setting(validate = validate_licensees)]
+    pub allow: Vec<String>,
+
+    /// Licenses that dependencies are not allowed to use.
+    #[setting(validate = validate_licensees)]
+    pub deny: Vec<String>,
+}
+
+impl ManifestLicenses {
+    pub fn get_allowed(&self) -> Vec<Licensee> {
+        parse_licensees(&self.allow)
+    }
+
+    pub fn get_denied(&self) -> Vec<Licensee> {
+        parse_licensees(&self.deny)
+    }
+
+    /// Return true if the license expression satisfies the allow and deny policy.
+    pub fn is_allowed(&self, license: &LicenseType) -> bool {
+        license.is_allowed(&self.get_allowed(), &self.get_denied())
+    }
+}
+
+fn parse_licensees(list: &[String]) -> Vec<Licensee> {
+    list.iter()
+        .filter_map(|value| Licensee::parse(value).ok())
+        .collect()
+}
+
+fn validate_licensees<D, C>(
+    value: &[String],
+    _partial: &D,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    for license in value {
+        if let Err(error) = Licensee::parse(license) {
+            return Err(ValidateError::new(format!(
+                "{license} is not a valid SPDX license identifier: {error}"
+            )));
+        }
+    }
+
+    Ok(())
+}
@@ -15,6 +15,13 @@ pub enum ManifestError {
     )]
     DetectionFailure { path: PathBuf },
 
+    #[diagnostic(code(manifest::licenses_not_allowed))]
+    #[error(
+        "Found {count} package(s) with a license that is not allowed by the {} policy.",
+        "[licenses]".style(Style::Symbol),
+    )]
+    LicensesNotAllowed { count: usize },
+
     #[diagnostic(code(manifest::missing_file))]
     #[error(
         "No {} manifest file found in {}.",
@@ -1,3 +1,5 @@
+use crate::install_setting::ManifestInstall;
+use crate::license_setting::ManifestLicenses;
 use crate::manifest_error::ManifestError;
 use crate::package_manifest::PackageManifest;
 use crate::workspace_manifest::WorkspaceManifest;
@@ -14,6 +16,22 @@ pub enum Manifest {
     Package(Box<PackageManifest>),
 }
 
+impl Manifest {
+    pub fn get_install_settings(&self) -> &ManifestInstall {
+        match self {
+            Self::Workspace(manifest) => &manifest.install,
+            Self::Package(manifest) => &manifest.install,
+        }
+    }
+
+    pub fn get_license_settings(&self) -> &ManifestLicenses {
+        match self {
+            Self::Workspace(manifest) => &manifest.licenses,
+            Self::Package(manifest) => &manifest.licenses,
+        }
+    }
+}
+
 pub struct ManifestLoader;
 
 impl ManifestLoader {
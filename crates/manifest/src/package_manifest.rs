@@ -1,6 +1,7 @@
-use crate::{build_setting::*, common_settings::*, install_setting::*};
-use espresso_common::{Category, LicenseType, PackageName, Version};
-use schematic::{validate, Config};
+use crate::{build_setting::*, common_settings::*, install_setting::*, license_setting::*};
+use espresso_common::{Category, LicenseType, PackageName, Version, VersionReq};
+use schematic::{validate, Config, ValidateError};
+use std::collections::BTreeMap;
 use url::Url;
 
 #[derive(Config, Debug, Eq, PartialEq)]
@@ -32,7 +33,7 @@ pub struct PackageManifestMetadata {
     pub publish: bool,
 }
 
-#[derive(Config, Debug, Eq, PartialEq)]
+#[derive(Config, Debug, PartialEq)]
 #[config(rename_all = "kebab-case")]
 pub struct PackageManifest {
     /// Metadata about the package.
@@ -47,7 +48,75 @@ pub struct PackageManifest {
     #[setting(nested)]
     pub install: ManifestInstall,
 
+    /// Controls which licenses dependencies may use.
+    #[setting(nested)]
+    pub licenses: ManifestLicenses,
+
     /// Dependencies for this package.
     pub dependencies: ManifestDependencies,
     pub dev_dependencies: ManifestDependencies,
+
+    /// Dependencies that must be provided by the consumer of this package.
+    #[setting(validate = validate_peer_dependencies)]
+    pub peer_dependencies: ManifestDependencies,
+
+    /// Dependencies that are not required for this package to function.
+    #[setting(validate = validate_optional_dependencies)]
+    pub optional_dependencies: ManifestDependencies,
+}
+
+impl PackageManifest {
+    /// Return all dependencies, regardless of kind.
+    pub fn get_all_dependencies(&self) -> BTreeMap<&PackageName, &VersionReq> {
+        let mut dependencies = BTreeMap::new();
+        dependencies.extend(&self.optional_dependencies);
+        dependencies.extend(&self.peer_dependencies);
+        dependencies.extend(&self.dev_dependencies);
+        dependencies.extend(&self.dependencies);
+        dependencies
+    }
+
+    /// Return dependencies that are required when the package is used,
+    /// which excludes dev dependencies.
+    pub fn get_production_dependencies(&self) -> BTreeMap<&PackageName, &VersionReq> {
+        let mut dependencies = BTreeMap::new();
+        dependencies.extend(&self.optional_dependencies);
+        dependencies.extend(&self.peer_dependencies);
+        dependencies.extend(&self.dependencies);
+        dependencies
+    }
+}
+
+fn validate_peer_dependencies<C>(
+    value: &ManifestDependencies,
+    partial: &PartialPackageManifest,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    validate_no_overlap(value, partial.dependencies.as_ref(), "a peer")
+}
+
+fn validate_optional_dependencies<C>(
+    value: &ManifestDependencies,
+    partial: &PartialPackageManifest,
+    _context: &C,
+) -> Result<(), ValidateError> {
+    validate_no_overlap(value, partial.dependencies.as_ref(), "an optional")
+}
+
+fn validate_no_overlap(
+    value: &ManifestDependencies,
+    dependencies: Option<&ManifestDependencies>,
+    kind: &str,
+) -> Result<(), ValidateError> {
+    if let Some(dependencies) = dependencies {
+        for name in value.keys() {
+            if dependencies.contains_key(name) {
+                return Err(ValidateError::new(format!(
+                    "{name} cannot be both a dependency and {kind} dependency"
+                )));
+            }
+        }
+    }
+
+    Ok(())
 }
@@ -1,4 +1,4 @@
-use crate::{common_settings::*, install_setting::*};
+use crate::{build_setting::*, common_settings::*, install_setting::*, license_setting::*};
 use relative_path::RelativePathBuf;
 use schematic::{validate, Config};
 
@@ -16,10 +16,18 @@ pub struct WorkspaceManifest {
     #[setting(nested)]
     pub workspace: WorkspaceManifestMetadata,
 
+    /// Build settings shared by all packages in the workspace.
+    #[setting(nested)]
+    pub build: WorkspaceManifestBuild,
+
     /// Controls how dependencies are installed.
     #[setting(nested)]
     pub install: ManifestInstall,
 
+    /// Controls which licenses dependencies may use.
+    #[setting(nested)]
+    pub licenses: ManifestLicenses,
+
     /// Dependencies for all packages in the workspace.
     pub dependencies: ManifestDependencies,
     pub dev_dependencies: ManifestDependencies,
@@ -26,15 +26,25 @@ name = "ns/pkg"
                 build: ManifestBuild {
                     decorators: None,
                     exclude: vec![],
+                    jsx: BuildJsx::default(),
+                    minify: BuildMinify::Enabled(false),
                     optimize_png: BuildOptimizePng::Enabled(true),
                     // optimize_svg: true,
+                    plugins: vec![],
+                    source_maps: BuildSourceMaps::Off,
+                    typescript_version: None,
                 },
                 dependencies: BTreeMap::new(),
                 dev_dependencies: BTreeMap::new(),
+                peer_dependencies: BTreeMap::new(),
+                optional_dependencies: BTreeMap::new(),
                 install: ManifestInstall {
                     linker: InstallLinker::NodeModules,
+                    minify: false,
                     target: EsTarget::Es2018,
+                    vendor_dir: None,
                 },
+                licenses: ManifestLicenses::default(),
                 package: PackageManifestMetadata {
                     name: PackageName::parse("ns/pkg").unwrap(),
                     version: None,
@@ -63,6 +73,8 @@ name = "ns/pkg"
 [build]
 exclude = ["*.png"]
 optimize-png = false
+source-maps = "external"
+typescript-version = "~5.4"
 "#,
             );
 
@@ -73,12 +85,172 @@ optimize-png = false
                 ManifestBuild {
                     decorators: None,
                     exclude: vec!["*.png".into()],
+                    jsx: BuildJsx::default(),
+                    minify: BuildMinify::Enabled(false),
                     optimize_png: BuildOptimizePng::Enabled(false),
                     // optimize_svg: false,
+                    plugins: vec![],
+                    source_maps: BuildSourceMaps::External,
+                    typescript_version: Some(VersionReq::parse("~5.4").unwrap()),
                 }
             );
         }
 
+        #[test]
+        fn can_set_plugins() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[[build.plugins]]
+name = "@swc/plugin-styled-components"
+config = { displayName = true, ssr = false }
+
+[[build.plugins]]
+path = "plugins/i18n.wasm"
+"#,
+            );
+
+            let manifest = ManifestLoader::load_package(sandbox.path()).unwrap();
+
+            assert_eq!(
+                manifest.build.plugins,
+                vec![
+                    BuildPlugin {
+                        name: Some("@swc/plugin-styled-components".into()),
+                        path: None,
+                        config: serde_json::json!({ "displayName": true, "ssr": false }),
+                    },
+                    BuildPlugin {
+                        name: None,
+                        path: Some("plugins/i18n.wasm".into()),
+                        config: serde_json::Value::Null,
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "plugins require either a name or a path")]
+        fn errors_for_plugin_without_name_or_path() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[[build.plugins]]
+config = { displayName = true }
+"#,
+            );
+
+            ManifestLoader::load_package(sandbox.path()).unwrap();
+        }
+
+        #[test]
+        #[should_panic(expected = "pragmas are only supported by the classic runtime")]
+        fn errors_for_pragma_with_automatic_runtime() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[build.jsx]
+pragma = "h"
+"#,
+            );
+
+            ManifestLoader::load_package(sandbox.path()).unwrap();
+        }
+
+        #[test]
+        fn can_set_jsx() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[build.jsx]
+runtime = "classic"
+pragma = "h"
+pragma-frag = "Fragment"
+development = true
+"#,
+            );
+
+            let manifest = ManifestLoader::load_package(sandbox.path()).unwrap();
+
+            assert_eq!(
+                manifest.build.jsx,
+                BuildJsx {
+                    runtime: BuildJsxRuntime::Classic,
+                    import_source: None,
+                    pragma: Some("h".into()),
+                    pragma_frag: Some("Fragment".into()),
+                    development: true,
+                }
+            );
+        }
+
+        #[test]
+        fn can_enable_minify() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[build]
+minify = true
+"#,
+            );
+
+            let manifest = ManifestLoader::load_package(sandbox.path()).unwrap();
+
+            assert_eq!(manifest.build.minify, BuildMinify::Enabled(true));
+            assert_eq!(
+                manifest.build.minify.get_options(),
+                Some(BuildMinifyOptions::default())
+            );
+        }
+
+        #[test]
+        fn can_set_minify_options() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[build.minify]
+mangle = false
+keep-class-names = false
+"#,
+            );
+
+            let manifest = ManifestLoader::load_package(sandbox.path()).unwrap();
+
+            assert_eq!(
+                manifest.build.minify,
+                BuildMinify::Options(BuildMinifyOptions {
+                    compress: true,
+                    mangle: false,
+                    keep_class_names: false,
+                })
+            );
+            assert!(manifest.build.minify.is_enabled());
+        }
+
         #[test]
         fn can_set_optimize_png_level() {
             let sandbox = create_empty_sandbox();
@@ -221,6 +393,106 @@ name = "ns/pkg"
         }
     }
 
+    mod peer_dependencies {
+        use super::*;
+
+        #[test]
+        fn can_set_deps() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[peer-dependencies]
+"ns/react" = "^18.0.0"
+"#,
+            );
+
+            let manifest = ManifestLoader::load_package(sandbox.path()).unwrap();
+
+            assert_eq!(
+                manifest.peer_dependencies,
+                BTreeMap::from_iter([(
+                    PackageName::parse("ns/react").unwrap(),
+                    VersionReq::parse("^18.0.0").unwrap()
+                )])
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "ns/react cannot be both a dependency and a peer")]
+        fn errors_if_also_a_dependency() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[dependencies]
+"ns/react" = "^18.0.0"
+
+[peer-dependencies]
+"ns/react" = "^18.0.0"
+"#,
+            );
+
+            ManifestLoader::load_package(sandbox.path()).unwrap();
+        }
+    }
+
+    mod optional_dependencies {
+        use super::*;
+
+        #[test]
+        fn can_set_deps() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[optional-dependencies]
+"ns/fsevents" = "2.3.0"
+"#,
+            );
+
+            let manifest = ManifestLoader::load_package(sandbox.path()).unwrap();
+
+            assert_eq!(
+                manifest.optional_dependencies,
+                BTreeMap::from_iter([(
+                    PackageName::parse("ns/fsevents").unwrap(),
+                    VersionReq::parse("2.3.0").unwrap()
+                )])
+            );
+        }
+
+        #[test]
+        #[should_panic(expected = "ns/fsevents cannot be both a dependency and an")]
+        fn errors_if_also_a_dependency() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[package]
+name = "ns/pkg"
+
+[dependencies]
+"ns/fsevents" = "2.3.0"
+
+[optional-dependencies]
+"ns/fsevents" = "2.3.0"
+"#,
+            );
+
+            ManifestLoader::load_package(sandbox.path()).unwrap();
+        }
+    }
+
     mod package {
         use super::*;
 
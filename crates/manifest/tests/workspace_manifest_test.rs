@@ -21,12 +21,16 @@ packages = ["*"]
         assert_eq!(
             manifest,
             WorkspaceManifest {
+                build: WorkspaceManifestBuild::default(),
                 dependencies: BTreeMap::new(),
                 dev_dependencies: BTreeMap::new(),
                 install: ManifestInstall {
                     linker: InstallLinker::NodeModules,
+                    minify: false,
                     target: EsTarget::Es2018,
+                    vendor_dir: None,
                 },
+                licenses: ManifestLicenses::default(),
                 workspace: WorkspaceManifestMetadata {
                     packages: vec!["*".into()]
                 }
@@ -34,6 +38,35 @@ packages = ["*"]
         );
     }
 
+    mod build {
+        use super::*;
+        use espresso_common::VersionReq;
+
+        #[test]
+        fn can_set_typescript_version() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[workspace]
+packages = ["*"]
+
+[build]
+typescript-version = "^5.4"
+"#,
+            );
+
+            let manifest = ManifestLoader::load_workspace(sandbox.path()).unwrap();
+
+            assert_eq!(
+                manifest.build,
+                WorkspaceManifestBuild {
+                    typescript_version: Some(VersionReq::parse("^5.4").unwrap()),
+                },
+            );
+        }
+    }
+
     mod install {
         use super::*;
 
@@ -66,7 +99,9 @@ packages = ["*"]
 
 [install]
 linker = "node-modules"
+minify = true
 target = "es2022"
+vendor-dir = "vendor"
 "#,
             );
 
@@ -76,12 +111,90 @@ target = "es2022"
                 manifest.install,
                 ManifestInstall {
                     linker: InstallLinker::NodeModules,
+                    minify: true,
                     target: EsTarget::Es2022,
+                    vendor_dir: Some("vendor".into()),
                 },
             );
         }
     }
 
+    mod licenses {
+        use super::*;
+        use espresso_common::LicenseType;
+
+        #[test]
+        #[should_panic(expected = "Unknown-License is not a valid SPDX license")]
+        fn errors_invalid_license() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[workspace]
+packages = ["*"]
+
+[licenses]
+allow = ["MIT", "Unknown-License"]
+"#,
+            );
+
+            ManifestLoader::load_workspace(sandbox.path()).unwrap();
+        }
+
+        #[test]
+        fn can_set_fields() {
+            let sandbox = create_empty_sandbox();
+            sandbox.create_file(
+                MANIFEST_NAME,
+                r#"
+[workspace]
+packages = ["*"]
+
+[licenses]
+allow = ["MIT", "Apache-2.0"]
+deny = ["GPL-3.0"]
+"#,
+            );
+
+            let manifest = ManifestLoader::load_workspace(sandbox.path()).unwrap();
+
+            assert_eq!(
+                manifest.licenses,
+                ManifestLicenses {
+                    allow: vec!["MIT".into(), "Apache-2.0".into()],
+                    deny: vec!["GPL-3.0".into()],
+                },
+            );
+        }
+
+        #[test]
+        fn evaluates_expressions() {
+            let licenses = ManifestLicenses {
+                allow: vec!["MIT".into(), "Apache-2.0".into()],
+                deny: vec!["GPL-3.0".into()],
+            };
+
+            assert!(licenses.is_allowed(&LicenseType::parse("MIT").unwrap()));
+            assert!(licenses.is_allowed(&LicenseType::parse("MIT OR GPL-3.0-only").unwrap()));
+            assert!(licenses.is_allowed(&LicenseType::parse("MIT AND Apache-2.0").unwrap()));
+            assert!(!licenses.is_allowed(&LicenseType::parse("MIT AND GPL-3.0-only").unwrap()));
+            assert!(!licenses.is_allowed(&LicenseType::parse("ISC").unwrap()));
+            assert!(!licenses
+                .is_allowed(&LicenseType::parse("Apache-2.0 WITH LLVM-exception").unwrap()));
+        }
+
+        #[test]
+        fn allows_everything_not_denied_when_allow_is_empty() {
+            let licenses = ManifestLicenses {
+                allow: vec![],
+                deny: vec!["GPL-3.0".into()],
+            };
+
+            assert!(licenses.is_allowed(&LicenseType::parse("ISC").unwrap()));
+            assert!(!licenses.is_allowed(&LicenseType::parse("GPL-3.0-only").unwrap()));
+        }
+    }
+
     mod workspace {
         use super::*;
 
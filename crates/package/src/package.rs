@@ -161,6 +161,24 @@ impl Package {
         self.locate_file_in_root(&["README", "ABOUT"])
     }
 
+    /// Return true if the declared license does not resemble the contents of
+    /// the located license file. If either is missing, there's nothing to compare.
+    pub fn has_license_mismatch(&self) -> miette::Result<bool> {
+        let (Some(license), Some(file)) = (&self.manifest.package.license, self.locate_license())
+        else {
+            return Ok(false);
+        };
+
+        debug!(
+            package = self.name(),
+            license = %license,
+            file = ?file,
+            "Comparing declared license with license file",
+        );
+
+        Ok(!license.matches_text(&fs::read_file(file)?))
+    }
+
     fn locate_file_in_root(&self, lookups: &[&str]) -> Option<PathBuf> {
         let mut files = vec![];
 
@@ -79,6 +79,79 @@ mod package {
         assert!(package.locate_license().is_none());
     }
 
+    mod license_mismatch {
+        use super::*;
+
+        const MIT_TEXT: &str = r#"MIT License
+
+Copyright (c) 2023 Espresso
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+"#;
+
+        fn create_package(license: &str) -> (starbase_sandbox::Sandbox, Package) {
+            let sandbox = create_sandbox("common");
+            sandbox.create_file(
+                "esp.toml",
+                format!("[package]\nname = \"ns/common\"\nlicense = \"{license}\""),
+            );
+
+            let package = Package::new(sandbox.path()).unwrap();
+
+            (sandbox, package)
+        }
+
+        #[test]
+        fn no_mismatch_without_file() {
+            let (_sandbox, package) = create_package("MIT");
+
+            assert!(!package.has_license_mismatch().unwrap());
+        }
+
+        #[test]
+        fn no_mismatch_when_text_matches() {
+            let (sandbox, package) = create_package("MIT");
+
+            sandbox.create_file("LICENSE", MIT_TEXT);
+
+            assert!(!package.has_license_mismatch().unwrap());
+        }
+
+        #[test]
+        fn no_mismatch_when_any_in_expression_matches() {
+            let (sandbox, package) = create_package("Apache-2.0 OR MIT");
+
+            sandbox.create_file("LICENSE", MIT_TEXT);
+
+            assert!(!package.has_license_mismatch().unwrap());
+        }
+
+        #[test]
+        fn mismatch_when_text_differs() {
+            let (sandbox, package) = create_package("Apache-2.0");
+
+            sandbox.create_file("LICENSE", MIT_TEXT);
+
+            assert!(package.has_license_mismatch().unwrap());
+        }
+    }
+
     #[test]
     fn locates_readme() {
         for file in [
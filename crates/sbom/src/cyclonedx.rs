@@ -0,0 +1,165 @@
+use crate::sbom::Sbom;
+use serde::Serialize;
+
+// https://cyclonedx.org/docs/1.5/json/
+
+#[derive(Debug, Serialize)]
+pub struct CycloneDxHash {
+    pub alg: String,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CycloneDxLicense {
+    pub expression: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CycloneDxExternalReference {
+    #[serde(rename = "type")]
+    pub type_of: String,
+    pub url: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycloneDxComponent {
+    #[serde(rename = "type")]
+    pub type_of: String,
+
+    #[serde(rename = "bom-ref")]
+    pub bom_ref: String,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub licenses: Vec<CycloneDxLicense>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hashes: Vec<CycloneDxHash>,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_references: Vec<CycloneDxExternalReference>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CycloneDxTool {
+    pub vendor: String,
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CycloneDxMetadata {
+    pub timestamp: String,
+    pub tools: Vec<CycloneDxTool>,
+    pub component: CycloneDxComponent,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycloneDxDependency {
+    #[serde(rename = "ref")]
+    pub ref_of: String,
+    pub depends_on: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycloneDxDocument {
+    pub bom_format: String,
+    pub spec_version: String,
+    pub version: u32,
+    pub metadata: CycloneDxMetadata,
+    pub components: Vec<CycloneDxComponent>,
+    pub dependencies: Vec<CycloneDxDependency>,
+}
+
+impl From<&Sbom> for CycloneDxDocument {
+    fn from(sbom: &Sbom) -> Self {
+        let mut components = vec![];
+        let mut dependencies = vec![];
+
+        for component in &sbom.components {
+            let mut external_references = vec![];
+
+            if let Some(repository) = &component.repository {
+                external_references.push(CycloneDxExternalReference {
+                    type_of: "vcs".into(),
+                    url: repository.to_string(),
+                });
+            }
+
+            if let Some(homepage) = &component.homepage {
+                external_references.push(CycloneDxExternalReference {
+                    type_of: "website".into(),
+                    url: homepage.to_string(),
+                });
+            }
+
+            components.push(CycloneDxComponent {
+                type_of: "library".into(),
+                bom_ref: component.get_ref(),
+                name: component.name.to_string(),
+                version: component
+                    .version
+                    .as_ref()
+                    .map(|version| version.to_string()),
+                description: component.description.clone(),
+                licenses: component
+                    .license
+                    .iter()
+                    .map(|license| CycloneDxLicense {
+                        expression: license.to_string(),
+                    })
+                    .collect(),
+                hashes: component
+                    .checksum
+                    .iter()
+                    .map(|checksum| CycloneDxHash {
+                        alg: "SHA-256".into(),
+                        content: checksum.to_owned(),
+                    })
+                    .collect(),
+                external_references,
+            });
+
+            dependencies.push(CycloneDxDependency {
+                ref_of: component.get_ref(),
+                depends_on: sbom.get_dependency_refs(component),
+            });
+        }
+
+        Self {
+            bom_format: "CycloneDX".into(),
+            spec_version: "1.5".into(),
+            version: 1,
+            metadata: CycloneDxMetadata {
+                timestamp: sbom.created.clone(),
+                tools: vec![CycloneDxTool {
+                    vendor: "moonrepo".into(),
+                    name: "espm".into(),
+                    version: env!("CARGO_PKG_VERSION").into(),
+                }],
+                component: CycloneDxComponent {
+                    type_of: "application".into(),
+                    bom_ref: sbom.name.clone(),
+                    name: sbom.name.clone(),
+                    version: None,
+                    description: None,
+                    licenses: vec![],
+                    hashes: vec![],
+                    external_references: vec![],
+                },
+            },
+            components,
+            dependencies,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod cyclonedx;
+mod sbom;
+mod sbom_format;
+mod spdx_document;
+
+pub use cyclonedx::*;
+pub use sbom::*;
+pub use sbom_format::*;
+pub use spdx_document::*;
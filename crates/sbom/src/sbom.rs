@@ -0,0 +1,276 @@
+use crate::cyclonedx::CycloneDxDocument;
+use crate::sbom_format::SbomFormat;
+use crate::spdx_document::SpdxDocument;
+use chrono::{SecondsFormat, Utc};
+use espresso_common::{LicenseType, PackageName, Version, VersionReq};
+use espresso_lockfile::{Lockfile, LockfilePackage};
+use espresso_manifest::MANIFEST_NAME;
+use espresso_package::Package;
+use espresso_store::{PackageItem, Store};
+use espresso_workspace::Workspace;
+use miette::IntoDiagnostic;
+use sha2::{Digest, Sha256};
+use starbase_utils::fs;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use tracing::debug;
+use url::Url;
+
+/// A package within the bill of materials, either a workspace
+/// package or a resolved dependency.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SbomComponent {
+    pub name: PackageName,
+    pub version: Option<Version>,
+    pub description: Option<String>,
+    pub license: Option<LicenseType>,
+    pub repository: Option<Url>,
+    pub homepage: Option<Url>,
+    /// SHA-256 checksum (hex) of the package archive.
+    pub checksum: Option<String>,
+    /// Dependencies mapped to their version requirement, when known.
+    pub dependencies: BTreeMap<PackageName, Option<VersionReq>>,
+    pub workspace: bool,
+}
+
+impl SbomComponent {
+    pub fn from_package(package: &Package, workspace: bool, dev: bool) -> Self {
+        let metadata = &package.manifest.package;
+        let dependencies = if dev {
+            package.manifest.get_all_dependencies()
+        } else {
+            package.manifest.get_production_dependencies()
+        };
+
+        Self {
+            name: metadata.name.clone(),
+            version: metadata.version.clone(),
+            description: if metadata.description.is_empty() {
+                None
+            } else {
+                Some(metadata.description.clone())
+            },
+            license: metadata.license.clone(),
+            repository: metadata.repository.clone(),
+            homepage: metadata.homepage.clone(),
+            checksum: None,
+            dependencies: dependencies
+                .into_iter()
+                .map(|(name, req)| (name.clone(), Some(req.clone())))
+                .collect(),
+            workspace,
+        }
+    }
+
+    /// Unique reference for this component, in the format of `name@version`.
+    pub fn get_ref(&self) -> String {
+        match &self.version {
+            Some(version) => format!("{}@{}", self.name, version),
+            None => self.name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Sbom {
+    pub name: String,
+    /// RFC 3339 timestamp of when the document was created.
+    pub created: String,
+    pub components: Vec<SbomComponent>,
+}
+
+impl Sbom {
+    /// Generate a bill of materials for the selected packages, and every workspace
+    /// package and locked dependency they depend on. Dev dependencies are only
+    /// included for the selected packages, and only when `dev` is enabled.
+    /// Metadata and checksums for dependencies are read from the store when
+    /// they've been installed.
+    pub fn generate(
+        workspace: &Workspace,
+        store: &Store,
+        packages: &[&Package],
+        dev: bool,
+    ) -> miette::Result<Sbom> {
+        let install = workspace.manifest.get_install_settings();
+        let lockfile = Lockfile::load(&workspace.root)?;
+        let mut components = vec![];
+
+        debug!(workspace = ?workspace.root, "Generating software bill of materials");
+
+        let selected_names = packages
+            .iter()
+            .map(|package| &package.manifest.package.name)
+            .collect::<HashSet<_>>();
+        let included_names = Self::collect_included_names(workspace, &lockfile, packages, dev)?;
+
+        for package in workspace.load_packages()?.values() {
+            let name = &package.manifest.package.name;
+
+            if included_names.contains(name) {
+                components.push(SbomComponent::from_package(
+                    package,
+                    true,
+                    dev && selected_names.contains(name),
+                ));
+            }
+        }
+
+        for locked in lockfile.packages {
+            if !included_names.contains(&locked.name) {
+                continue;
+            }
+
+            let item = PackageItem {
+                minified: install.minify,
+                package: &locked.name,
+                target: &install.target,
+                version: &locked.version,
+            };
+            let package_dir = store.get_item_dir(&item);
+
+            let mut component = if package_dir.join(MANIFEST_NAME).exists() {
+                SbomComponent::from_package(&Package::new(&package_dir)?, false, false)
+            } else {
+                debug!(
+                    package = locked.name.as_str(),
+                    "Package has not been installed, only using lockfile metadata",
+                );
+
+                SbomComponent {
+                    name: locked.name.clone(),
+                    ..SbomComponent::default()
+                }
+            };
+
+            // Requirements are only known when the package has been installed
+            component.version = Some(locked.version.clone());
+            component.dependencies = locked
+                .dependencies
+                .iter()
+                .map(|name| {
+                    let req = component.dependencies.get(name).cloned().flatten();
+
+                    (name.clone(), req)
+                })
+                .collect();
+            component.checksum = Self::resolve_checksum(&locked, &store.get_item_archive(&item))?;
+
+            components.push(component);
+        }
+
+        Ok(Sbom {
+            // A single package (like an app) is described by name
+            name: if packages.len() == 1 {
+                packages[0].manifest.package.name.to_string()
+            } else {
+                fs::file_name(&workspace.root)
+            },
+            created: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+            components,
+        })
+    }
+
+    /// Resolve the dependencies of a component to the references (`name@version`)
+    /// of other components. Multiple versions of the same package may be locked, so
+    /// the highest version that satisfies the requirement is used.
+    pub fn get_dependency_refs(&self, component: &SbomComponent) -> Vec<String> {
+        component
+            .dependencies
+            .iter()
+            .filter_map(|(name, req)| {
+                self.components
+                    .iter()
+                    .filter(|dependency| {
+                        &dependency.name == name
+                            && match (req, &dependency.version) {
+                                (Some(req), Some(version)) => req.matches(version),
+                                _ => true,
+                            }
+                    })
+                    .max_by(|a, b| a.version.cmp(&b.version))
+                    .map(|dependency| dependency.get_ref())
+            })
+            .collect()
+    }
+
+    pub fn render(&self, format: SbomFormat) -> miette::Result<String> {
+        match format {
+            SbomFormat::CyclonedxJson => {
+                serde_json::to_string_pretty(&CycloneDxDocument::from(self)).into_diagnostic()
+            }
+            SbomFormat::SpdxJson => {
+                serde_json::to_string_pretty(&SpdxDocument::from(self)).into_diagnostic()
+            }
+        }
+    }
+
+    // Walk the dependencies of the selected packages, through workspace packages
+    // and the lockfile. Locked packages are only linked by name, so every locked
+    // version of a dependency is included.
+    fn collect_included_names(
+        workspace: &Workspace,
+        lockfile: &Lockfile,
+        packages: &[&Package],
+        dev: bool,
+    ) -> miette::Result<HashSet<PackageName>> {
+        let workspace_packages = workspace.load_packages()?;
+        let mut included = HashSet::new();
+        let mut pending = vec![];
+
+        for package in packages {
+            included.insert(package.manifest.package.name.clone());
+
+            pending.extend(
+                SbomComponent::from_package(package, true, dev)
+                    .dependencies
+                    .into_keys(),
+            );
+        }
+
+        while let Some(name) = pending.pop() {
+            if included.contains(&name) {
+                continue;
+            }
+
+            if let Some(package) = workspace_packages.get(&name) {
+                pending.extend(
+                    package
+                        .manifest
+                        .get_production_dependencies()
+                        .into_keys()
+                        .cloned(),
+                );
+            } else {
+                for locked in lockfile
+                    .packages
+                    .iter()
+                    .filter(|locked| locked.name == name)
+                {
+                    pending.extend(locked.dependencies.iter().cloned());
+                }
+            }
+
+            included.insert(name);
+        }
+
+        Ok(included)
+    }
+
+    // Prefer the lockfile checksum, otherwise hash the archive in the store cache.
+    fn resolve_checksum(
+        locked: &LockfilePackage,
+        archive_file: &Path,
+    ) -> miette::Result<Option<String>> {
+        if let Some(checksum) = &locked.checksum {
+            return Ok(Some(checksum.to_owned()));
+        }
+
+        if !archive_file.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read(archive_file).into_diagnostic()?;
+
+        Ok(Some(format!("{:x}", Sha256::digest(contents))))
+    }
+}
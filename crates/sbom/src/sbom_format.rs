@@ -0,0 +1,11 @@
+use clap::ValueEnum;
+use schematic::{derive_enum, ConfigEnum};
+
+derive_enum!(
+    #[derive(ConfigEnum, Copy, Default, ValueEnum)]
+    pub enum SbomFormat {
+        #[default]
+        CyclonedxJson,
+        SpdxJson,
+    }
+);
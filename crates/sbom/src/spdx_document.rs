@@ -0,0 +1,190 @@
+use crate::sbom::{Sbom, SbomComponent};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+// https://spdx.github.io/spdx-spec/v2.3/
+
+const NOASSERTION: &str = "NOASSERTION";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxChecksum {
+    pub algorithm: String,
+    pub checksum_value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxCreationInfo {
+    pub created: String,
+    pub creators: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxPackage {
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+
+    pub name: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version_info: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+
+    pub download_location: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+
+    pub license_concluded: String,
+
+    pub license_declared: String,
+
+    pub copyright_text: String,
+
+    pub files_analyzed: bool,
+
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub checksums: Vec<SpdxChecksum>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxRelationship {
+    pub spdx_element_id: String,
+    pub relationship_type: String,
+    pub related_spdx_element: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpdxDocument {
+    pub spdx_version: String,
+
+    pub data_license: String,
+
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+
+    pub name: String,
+
+    pub document_namespace: String,
+
+    pub creation_info: SpdxCreationInfo,
+
+    pub packages: Vec<SpdxPackage>,
+
+    pub relationships: Vec<SpdxRelationship>,
+}
+
+// Identifiers may only contain letters, numbers, `.`, and `-`.
+fn create_spdx_id(component: &SbomComponent) -> String {
+    let id = component
+        .get_ref()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+
+    format!("SPDXRef-Package-{id}")
+}
+
+impl From<&Sbom> for SpdxDocument {
+    fn from(sbom: &Sbom) -> Self {
+        let ids = sbom
+            .components
+            .iter()
+            .map(|component| (component.get_ref(), create_spdx_id(component)))
+            .collect::<BTreeMap<_, _>>();
+        let mut packages = vec![];
+        let mut relationships = vec![];
+        let mut hasher = Sha256::new();
+
+        for component in &sbom.components {
+            let spdx_id = create_spdx_id(component);
+
+            hasher.update(component.get_ref().as_bytes());
+
+            packages.push(SpdxPackage {
+                spdx_id: spdx_id.clone(),
+                name: component.name.to_string(),
+                version_info: component
+                    .version
+                    .as_ref()
+                    .map(|version| version.to_string()),
+                description: component.description.clone(),
+                download_location: component
+                    .repository
+                    .as_ref()
+                    .map(|repository| repository.to_string())
+                    .unwrap_or_else(|| NOASSERTION.into()),
+                homepage: component.homepage.as_ref().map(|url| url.to_string()),
+                license_concluded: NOASSERTION.into(),
+                license_declared: component
+                    .license
+                    .as_ref()
+                    .map(|license| license.to_string())
+                    .unwrap_or_else(|| NOASSERTION.into()),
+                copyright_text: NOASSERTION.into(),
+                files_analyzed: false,
+                checksums: component
+                    .checksum
+                    .iter()
+                    .map(|checksum| SpdxChecksum {
+                        algorithm: "SHA256".into(),
+                        checksum_value: checksum.to_owned(),
+                    })
+                    .collect(),
+            });
+
+            if component.workspace {
+                relationships.push(SpdxRelationship {
+                    spdx_element_id: "SPDXRef-DOCUMENT".into(),
+                    relationship_type: "DESCRIBES".into(),
+                    related_spdx_element: spdx_id.clone(),
+                });
+            }
+
+            for dependency_ref in sbom.get_dependency_refs(component) {
+                if let Some(dependency_id) = ids.get(&dependency_ref) {
+                    relationships.push(SpdxRelationship {
+                        spdx_element_id: spdx_id.clone(),
+                        relationship_type: "DEPENDS_ON".into(),
+                        related_spdx_element: dependency_id.to_owned(),
+                    });
+                }
+            }
+        }
+
+        // The namespace must be unique per document, so derive it
+        // from the components and when the document was created
+        hasher.update(sbom.created.as_bytes());
+
+        Self {
+            spdx_version: "SPDX-2.3".into(),
+            data_license: "CC0-1.0".into(),
+            spdx_id: "SPDXRef-DOCUMENT".into(),
+            name: sbom.name.clone(),
+            document_namespace: format!(
+                "https://espresso.build/spdxdocs/{}-{:x}",
+                sbom.name,
+                hasher.finalize()
+            ),
+            creation_info: SpdxCreationInfo {
+                created: sbom.created.clone(),
+                creators: vec![format!("Tool: espm-{}", env!("CARGO_PKG_VERSION"))],
+            },
+            packages,
+            relationships,
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use espresso_common::{PackageName, VersionReq};
+use espresso_sbom::*;
+use espresso_store::Store;
+use espresso_workspace::{SelectQuery, Workspace};
+use starbase_sandbox::{create_empty_sandbox, Sandbox};
+use std::collections::BTreeMap;
+
+fn create_sbom_sandbox() -> Sandbox {
+    let sandbox = create_empty_sandbox();
+    sandbox.create_file(
+        "esp.toml",
+        r#"
+[package]
+name = "ns/app"
+version = "1.0.0"
+license = "MIT"
+repository = "https://github.com/ns/app"
+homepage = "https://ns.dev"
+
+[dependencies]
+"ns/pkg-a" = "^1.0.0"
+"#,
+    );
+    sandbox.create_file(
+        "espm.lock",
+        r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+checksum = "abc123"
+dependencies = ["ns/pkg-b"]
+
+[[package]]
+name = "ns/pkg-b"
+version = "2.0.0"
+"#,
+    );
+    sandbox.create_file(
+        ".espresso/packages/ns/pkg-a/v1.0.0/es2018/esp.toml",
+        "[package]\nname = \"ns/pkg-a\"\nversion = \"1.0.0\"\nlicense = \"Apache-2.0\"",
+    );
+    sandbox.create_file(".espresso/cache/ns_pkg-b_v2.0.0_es2018.tar.xz", "archive");
+    sandbox
+}
+
+fn generate_sbom_with(sandbox: &Sandbox, query: SelectQuery, dev: bool) -> Sbom {
+    let workspace = Workspace::load_from(sandbox.path()).unwrap();
+    let store = Store::load_from(sandbox.path().join(".espresso")).unwrap();
+    let packages = workspace.select_packages(query).unwrap();
+
+    Sbom::generate(&workspace, &store, &packages, dev).unwrap()
+}
+
+fn generate_sbom(sandbox: &Sandbox) -> Sbom {
+    generate_sbom_with(sandbox, SelectQuery::default(), false)
+}
+
+fn get_refs(sbom: &Sbom) -> Vec<String> {
+    sbom.components
+        .iter()
+        .map(|component| component.get_ref())
+        .collect()
+}
+
+mod sbom {
+    use super::*;
+
+    #[test]
+    fn includes_workspace_packages() {
+        let sandbox = create_sbom_sandbox();
+        let sbom = generate_sbom(&sandbox);
+        let app = &sbom.components[0];
+
+        assert_eq!(app.get_ref(), "ns/app@1.0.0");
+        assert_eq!(app.license.as_ref().unwrap().to_string(), "MIT");
+        assert_eq!(
+            app.repository.as_ref().unwrap().as_str(),
+            "https://github.com/ns/app"
+        );
+        assert_eq!(
+            app.dependencies,
+            BTreeMap::from_iter([(
+                PackageName::parse("ns/pkg-a").unwrap(),
+                Some(VersionReq::parse("^1.0.0").unwrap())
+            )])
+        );
+        assert!(app.workspace);
+    }
+
+    #[test]
+    fn includes_locked_dependencies() {
+        let sandbox = create_sbom_sandbox();
+        let sbom = generate_sbom(&sandbox);
+
+        assert_eq!(sbom.components.len(), 3);
+
+        let a = &sbom.components[1];
+
+        assert_eq!(a.get_ref(), "ns/pkg-a@1.0.0");
+        assert_eq!(a.license.as_ref().unwrap().to_string(), "Apache-2.0");
+        assert_eq!(a.checksum.as_deref(), Some("abc123"));
+        assert!(!a.workspace);
+    }
+
+    #[test]
+    fn hashes_cached_archive_without_lockfile_checksum() {
+        let sandbox = create_sbom_sandbox();
+        let sbom = generate_sbom(&sandbox);
+        let b = &sbom.components[2];
+
+        assert_eq!(b.get_ref(), "ns/pkg-b@2.0.0");
+        assert!(b.license.is_none());
+        assert_eq!(
+            b.checksum.as_deref(),
+            Some("0eb3e36bfb24dcd9bb1d1bece1531216b59539a8fde17ee80224af0653c92aa3")
+        );
+    }
+
+    #[test]
+    fn links_dependencies_to_matching_versions() {
+        let sandbox = create_sbom_sandbox();
+        sandbox.create_file(
+            "espm.lock",
+            r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+dependencies = ["ns/pkg-b"]
+
+[[package]]
+name = "ns/pkg-a"
+version = "2.0.0"
+
+[[package]]
+name = "ns/pkg-b"
+version = "2.0.0"
+"#,
+        );
+
+        let sbom = generate_sbom(&sandbox);
+
+        assert_eq!(
+            sbom.components
+                .iter()
+                .map(|component| (component.get_ref(), sbom.get_dependency_refs(component)))
+                .collect::<Vec<_>>(),
+            vec![
+                (
+                    String::from("ns/app@1.0.0"),
+                    vec![String::from("ns/pkg-a@1.0.0")]
+                ),
+                (
+                    String::from("ns/pkg-a@1.0.0"),
+                    vec![String::from("ns/pkg-b@2.0.0")]
+                ),
+                (String::from("ns/pkg-a@2.0.0"), vec![]),
+                (String::from("ns/pkg-b@2.0.0"), vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn excludes_dev_dependencies() {
+        let sandbox = create_sbom_sandbox();
+        sandbox.create_file(
+            "espm.lock",
+            r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+
+[[package]]
+name = "ns/test-utils"
+version = "1.0.0"
+"#,
+        );
+        sandbox.create_file(
+            "esp.toml",
+            r#"
+[package]
+name = "ns/app"
+version = "1.0.0"
+
+[dependencies]
+"ns/pkg-a" = "^1.0.0"
+
+[dev-dependencies]
+"ns/test-utils" = "^1.0.0"
+"#,
+        );
+
+        let sbom = generate_sbom(&sandbox);
+
+        assert_eq!(get_refs(&sbom), ["ns/app@1.0.0", "ns/pkg-a@1.0.0"]);
+        assert_eq!(sbom.components[0].dependencies.len(), 1);
+
+        let sbom = generate_sbom_with(&sandbox, SelectQuery::default(), true);
+
+        assert_eq!(
+            get_refs(&sbom),
+            ["ns/app@1.0.0", "ns/pkg-a@1.0.0", "ns/test-utils@1.0.0"]
+        );
+    }
+
+    #[test]
+    fn only_includes_selected_packages_and_their_dependencies() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("esp.toml", "[workspace]\npackages = [\"*\"]");
+        sandbox.create_file(
+            "app/esp.toml",
+            r#"
+[package]
+name = "ns/app"
+
+[dependencies]
+"ns/lib" = "*"
+"#,
+        );
+        sandbox.create_file(
+            "lib/esp.toml",
+            r#"
+[package]
+name = "ns/lib"
+
+[dependencies]
+"ns/pkg-a" = "^1.0.0"
+
+[dev-dependencies]
+"ns/pkg-b" = "^2.0.0"
+"#,
+        );
+        sandbox.create_file("other/esp.toml", "[package]\nname = \"ns/other\"");
+        sandbox.create_file(
+            "espm.lock",
+            r#"
+[[package]]
+name = "ns/pkg-a"
+version = "1.0.0"
+
+[[package]]
+name = "ns/pkg-b"
+version = "2.0.0"
+"#,
+        );
+
+        let names = vec![PackageName::parse("ns/app").unwrap()];
+        let sbom = generate_sbom_with(
+            &sandbox,
+            SelectQuery {
+                names: Some(&names),
+                ..SelectQuery::default()
+            },
+            true,
+        );
+
+        assert_eq!(sbom.name, "ns/app");
+        assert_eq!(get_refs(&sbom), ["ns/app", "ns/lib", "ns/pkg-a@1.0.0"]);
+    }
+
+    #[test]
+    fn renders_cyclonedx() {
+        let sandbox = create_sbom_sandbox();
+        let output = generate_sbom(&sandbox)
+            .render(SbomFormat::CyclonedxJson)
+            .unwrap();
+
+        assert!(output.contains("\"bomFormat\": \"CycloneDX\""));
+        assert!(output.contains("\"bom-ref\": \"ns/pkg-a@1.0.0\""));
+        assert!(output.contains("\"expression\": \"Apache-2.0\""));
+        assert!(output.contains("\"alg\": \"SHA-256\""));
+        assert!(output.contains("\"url\": \"https://github.com/ns/app\""));
+        assert!(output.contains("\"ref\": \"ns/pkg-a@1.0.0\""));
+        assert!(output.contains("\"ns/pkg-b@2.0.0\""));
+    }
+
+    #[test]
+    fn renders_spdx() {
+        let sandbox = create_sbom_sandbox();
+        let output = generate_sbom(&sandbox)
+            .render(SbomFormat::SpdxJson)
+            .unwrap();
+
+        assert!(output.contains("\"spdxVersion\": \"SPDX-2.3\""));
+        assert!(output.contains("\"SPDXID\": \"SPDXRef-Package-ns-pkg-a-1.0.0\""));
+        assert!(output.contains("\"licenseDeclared\": \"Apache-2.0\""));
+        assert!(output.contains("\"checksumValue\": \"abc123\""));
+        assert!(output.contains("\"relationshipType\": \"DESCRIBES\""));
+        assert!(output.contains("\"relationshipType\": \"DEPENDS_ON\""));
+    }
+}
@@ -1,3 +1,4 @@
+mod npm_registry;
 mod storage_item;
 mod store;
 mod store_error;
@@ -0,0 +1,130 @@
+use crate::store::Store;
+use crate::store_error::StoreError;
+use espresso_common::{Version, VersionReq};
+use miette::IntoDiagnostic;
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use starbase_utils::json;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, warn};
+
+// How long the list of published versions is cached for
+const VERSIONS_CACHE_TTL: Duration = Duration::from_secs(60 * 60 * 24);
+
+#[derive(Deserialize)]
+struct NpmPackageMetadata {
+    versions: BTreeMap<String, IgnoredAny>,
+}
+
+impl Store {
+    /// Resolve the highest published version of an npm package that satisfies
+    /// the requirement, using the registry's abbreviated metadata.
+    pub async fn resolve_npm_version(
+        &self,
+        package: &str,
+        req: &VersionReq,
+    ) -> miette::Result<Version> {
+        let version = self
+            .load_npm_versions(package)
+            .await?
+            .into_iter()
+            .filter_map(|version| Version::parse(&version).ok())
+            // Pre-releases must be explicitly requested
+            .filter(|version| req.matches(version))
+            .max();
+
+        match version {
+            Some(version) => {
+                debug!(
+                    package,
+                    req = req.to_string(),
+                    version = version.to_string(),
+                    "Resolved npm package version",
+                );
+
+                Ok(version)
+            }
+            None => Err(StoreError::NpmVersionNotFound {
+                package: package.to_owned(),
+                req: req.to_owned(),
+            }
+            .into()),
+        }
+    }
+
+    fn get_npm_versions_cache(&self, package: &str) -> PathBuf {
+        self.cache_dir
+            .join(format!("npm_{}_versions.json", package.replace('/', "_")))
+    }
+
+    async fn load_npm_versions(&self, package: &str) -> miette::Result<Vec<String>> {
+        let cache_file = self.get_npm_versions_cache(package);
+
+        let is_fresh = cache_file
+            .metadata()
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age < VERSIONS_CACHE_TTL);
+
+        if is_fresh {
+            debug!(package, cache = ?cache_file, "Using cached npm package versions");
+
+            return Ok(json::read_file(&cache_file)?);
+        }
+
+        match self.fetch_npm_versions(package).await {
+            Ok(versions) => {
+                json::write_file(&cache_file, &versions, false)?;
+
+                Ok(versions)
+            }
+            Err(error) if cache_file.exists() => {
+                warn!(
+                    package,
+                    "Failed to fetch npm package versions, using stale cache: {error}"
+                );
+
+                Ok(json::read_file(&cache_file)?)
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    async fn fetch_npm_versions(&self, package: &str) -> miette::Result<Vec<String>> {
+        let url = format!("https://registry.npmjs.org/{package}");
+
+        debug!(package, url, "Fetching npm package versions");
+
+        let response = reqwest::Client::new()
+            .get(&url)
+            .header("Accept", "application/vnd.npm.install-v1+json")
+            .send()
+            .await
+            .map_err(|error| StoreError::Http { error })?;
+        let status = response.status();
+
+        if status.as_u16() == 404 {
+            return Err(StoreError::DownloadNotFound { url }.into());
+        }
+
+        if !status.is_success() {
+            return Err(StoreError::DownloadFailed {
+                url,
+                status: status.to_string(),
+            }
+            .into());
+        }
+
+        let contents = response
+            .bytes()
+            .await
+            .map_err(|error| StoreError::Http { error })?;
+
+        let metadata: NpmPackageMetadata = serde_json::from_slice(&contents).into_diagnostic()?;
+
+        Ok(metadata.versions.into_keys().collect())
+    }
+}
@@ -10,11 +10,23 @@ pub trait StorageItem {
 }
 
 pub struct PackageItem<'app> {
+    pub minified: bool,
     pub package: &'app PackageName,
     pub target: &'app EsTarget,
     pub version: &'app Version,
 }
 
+impl<'app> PackageItem<'app> {
+    // Minified builds are a separate flavor of the same target
+    fn get_flavor(&self) -> String {
+        if self.minified {
+            format!("{}-min", self.target)
+        } else {
+            self.target.to_string()
+        }
+    }
+}
+
 impl<'app> StorageItem for PackageItem<'app> {
     fn get_archive_ext(&self) -> &str {
         "tar.xz"
@@ -34,7 +46,7 @@ impl<'app> StorageItem for PackageItem<'app> {
         PathBuf::from(components.0)
             .join(components.1)
             .join(format!("v{}", self.version))
-            .join(self.target.to_string())
+            .join(self.get_flavor())
     }
 
     fn to_file_prefix(&self) -> String {
@@ -42,7 +54,10 @@ impl<'app> StorageItem for PackageItem<'app> {
 
         format!(
             "{}_{}_v{}_{}",
-            components.0, components.1, self.version, self.target
+            components.0,
+            components.1,
+            self.version,
+            self.get_flavor()
         )
     }
 }
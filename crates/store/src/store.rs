@@ -17,6 +17,9 @@ pub struct Store {
     pub packages_dir: PathBuf,
     pub root: PathBuf,
 
+    /// Directory of vendored packages, which take precedence over the store.
+    pub vendor_dir: Option<PathBuf>,
+
     locks: Arc<Mutex<HashMap<String, Arc<Mutex<()>>>>>,
 }
 
@@ -54,11 +57,48 @@ impl Store {
             cache_dir,
             packages_dir,
             root: root.to_path_buf(),
+            vendor_dir: None,
             locks: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
+    pub fn get_item_archive(&self, item: &impl StorageItem) -> PathBuf {
+        self.cache_dir.join(format!(
+            "{}.{}",
+            item.to_file_prefix(),
+            item.get_archive_ext()
+        ))
+    }
+
+    /// Return the directory an item resolves to, which is the vendored
+    /// directory when it has been vendored, otherwise the store directory.
+    pub fn get_item_dir(&self, item: &impl StorageItem) -> PathBuf {
+        self.get_vendored_item_dir(item)
+            .unwrap_or_else(|| self.get_stored_item_dir(item))
+    }
+
+    pub fn get_stored_item_dir(&self, item: &impl StorageItem) -> PathBuf {
+        self.packages_dir.join(item.to_file_path())
+    }
+
+    pub fn get_vendored_item_dir(&self, item: &impl StorageItem) -> Option<PathBuf> {
+        self.vendor_dir
+            .as_ref()
+            .map(|vendor_dir| vendor_dir.join(item.to_file_path()))
+            .filter(|dir| dir.exists())
+    }
+
     pub async fn store_item(&self, url: &str, item: impl StorageItem) -> miette::Result<PathBuf> {
+        if let Some(vendored_dir) = self.get_vendored_item_dir(&item) {
+            debug!(
+                item = item.get_label(),
+                vendored_dir = ?vendored_dir,
+                "Package has been vendored, skipping download",
+            );
+
+            return Ok(vendored_dir);
+        }
+
         let mut locks = self.locks.lock().await;
 
         // Create a lock for this item, so that we avoid multiple processes
@@ -76,7 +116,7 @@ impl Store {
         // After we've acquired the lock, we can check if the item already
         // exists in the store. If we do this before the lock, other processes would
         // return true while the archive is being unpacked, resulting in breakages!
-        let output_dir = self.packages_dir.join(item.to_file_path());
+        let output_dir = self.get_stored_item_dir(&item);
         let _fs_lock = fs::lock_directory(&output_dir)?;
 
         if output_dir.exists() && !output_dir.join(".lock").exists() {
@@ -95,11 +135,7 @@ impl Store {
         url: &str,
         item: &impl StorageItem,
     ) -> miette::Result<PathBuf> {
-        let archive_file = self.cache_dir.join(format!(
-            "{}.{}",
-            item.to_file_prefix(),
-            item.get_archive_ext()
-        ));
+        let archive_file = self.get_item_archive(item);
 
         if archive_file.exists() {
             debug!(
@@ -159,7 +195,7 @@ impl Store {
         archive_file: &Path,
         item: &impl StorageItem,
     ) -> miette::Result<PathBuf> {
-        let output_dir = self.packages_dir.join(item.to_file_path());
+        let output_dir = self.get_stored_item_dir(item);
 
         if output_dir.exists() && !output_dir.join(".lock").exists() {
             debug!(
@@ -1,5 +1,7 @@
+use espresso_common::VersionReq;
 use miette::Diagnostic;
 use starbase_styles::{Style, Stylize};
+use std::path::PathBuf;
 use thiserror::Error;
 
 #[derive(Debug, Diagnostic, Error)]
@@ -18,4 +20,20 @@ pub enum StoreError {
     #[diagnostic(code(store::download_archive::failed))]
     #[error("Failed to download package from {} ({status}).", .url.style(Style::Url))]
     DownloadFailed { url: String, status: String },
+
+    #[diagnostic(code(store::missing_item))]
+    #[error(
+        "Package {} has not been installed into the store at {}.",
+        .label.style(Style::Id),
+        .dir.style(Style::Path),
+    )]
+    MissingItem { label: String, dir: PathBuf },
+
+    #[diagnostic(code(store::npm::version_not_found))]
+    #[error(
+        "No published version of {} satisfies {}.",
+        .package.style(Style::Id),
+        .req.to_string().style(Style::Symbol),
+    )]
+    NpmVersionNotFound { package: String, req: VersionReq },
 }
@@ -314,6 +314,8 @@ derive_enum!(
         Es2020,
         Es2021,
         Es2022,
+        Es2023,
+        Es2024,
         Esnext,
         #[variant(fallback)]
         Other(String),
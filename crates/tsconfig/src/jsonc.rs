@@ -0,0 +1,75 @@
+/// Strip comments and trailing commas from JSONC content, like `tsconfig.json`,
+/// so that it can be parsed as JSON. Line breaks are preserved, so that the
+/// positions of parse errors still match the original content.
+pub fn strip_jsonc(content: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+
+    // Position of the last comma, which is removed
+    // if only whitespace follows before closing
+    let mut comma: Option<usize> = None;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                comma = None;
+                output.push(ch);
+
+                while let Some(ch) = chars.next() {
+                    output.push(ch);
+
+                    if ch == '\\' {
+                        if let Some(escaped) = chars.next() {
+                            output.push(escaped);
+                        }
+                    } else if ch == '"' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        output.push(ch);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+
+                let mut previous = '\0';
+
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        output.push(ch);
+                    } else if previous == '*' && ch == '/' {
+                        break;
+                    }
+
+                    previous = ch;
+                }
+            }
+            ',' => {
+                comma = Some(output.len());
+                output.push(ch);
+            }
+            '}' | ']' => {
+                if let Some(index) = comma.take() {
+                    output.replace_range(index..=index, " ");
+                }
+
+                output.push(ch);
+            }
+            _ => {
+                if !ch.is_whitespace() {
+                    comma = None;
+                }
+
+                output.push(ch);
+            }
+        }
+    }
+
+    output
+}
@@ -1,8 +1,14 @@
 mod compiler_options;
+mod jsonc;
+mod tsconfig_error;
+mod tsconfig_loader;
 
 pub use compiler_options::*;
+pub use jsonc::*;
 use relative_path::RelativePathBuf;
 use schematic::Config;
+pub use tsconfig_error::*;
+pub use tsconfig_loader::*;
 
 #[derive(Config)]
 #[config(serde(untagged))]
@@ -12,7 +18,7 @@ pub enum TsConfigExtends {
 }
 
 #[derive(Config)]
-#[config(rename_all = "camelCase")]
+#[config(allow_unknown_fields, rename_all = "camelCase")]
 pub struct TsConfig {
     pub compile_on_save: Option<bool>,
 
@@ -0,0 +1,43 @@
+use miette::Diagnostic;
+use starbase_styles::{Style, Stylize};
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Debug, Diagnostic, Error)]
+pub enum TsConfigError {
+    #[diagnostic(code(tsconfig::extends::circular))]
+    #[error(
+        "Circular {} detected, {} extends itself.",
+        "extends".style(Style::Label),
+        .path.style(Style::Path),
+    )]
+    CircularExtends { path: PathBuf },
+
+    #[diagnostic(code(tsconfig::extends::not_found))]
+    #[error(
+        "Unable to resolve {} extended by {}.",
+        .specifier.style(Style::File),
+        .path.style(Style::Path),
+    )]
+    ExtendsNotFound { specifier: String, path: PathBuf },
+
+    #[diagnostic(code(tsconfig::extends::invalid))]
+    #[error(
+        "Invalid {} in {}, expected a string or a list of strings.",
+        "extends".style(Style::Label),
+        .path.style(Style::Path),
+    )]
+    InvalidExtends { path: PathBuf },
+
+    #[diagnostic(code(tsconfig::missing_file))]
+    #[error("No tsconfig found at {}.", .path.style(Style::Path))]
+    MissingFile { path: PathBuf },
+
+    #[diagnostic(code(tsconfig::parse_failed))]
+    #[error("Failed to parse tsconfig {}.", .path.style(Style::Path))]
+    ParseFailed {
+        path: PathBuf,
+        #[source]
+        error: serde_json::Error,
+    },
+}
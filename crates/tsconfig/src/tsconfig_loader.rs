@@ -0,0 +1,376 @@
+use crate::jsonc::strip_jsonc;
+use crate::tsconfig_error::TsConfigError;
+use crate::PartialTsConfig;
+use serde_json::{Map, Value};
+use starbase_utils::fs;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use tracing::debug;
+
+// Compiler options that are paths, relative from the file that defines them
+const PATH_OPTIONS: [&str; 6] = [
+    "baseUrl",
+    "declarationDir",
+    "outDir",
+    "outFile",
+    "rootDir",
+    "tsBuildInfoFile",
+];
+
+const PATH_LIST_OPTIONS: [&str; 2] = ["rootDirs", "typeRoots"];
+
+const PATH_LIST_FIELDS: [&str; 3] = ["exclude", "files", "include"];
+
+/// A tsconfig with its `extends` chain resolved and merged.
+#[derive(Debug)]
+pub struct ResolvedTsConfig {
+    /// The merged config, with all paths relative from the loaded file.
+    pub config: PartialTsConfig,
+
+    /// Every file that was loaded, starting with the requested file,
+    /// followed by the files it extends, in the order they were extended.
+    pub files: Vec<PathBuf>,
+
+    /// The file that set each option, keyed by its name. Compiler options
+    /// are prefixed, for example `include` and `compilerOptions.strict`.
+    pub sources: BTreeMap<String, PathBuf>,
+}
+
+impl ResolvedTsConfig {
+    /// Return the file that set the provided option, if it has been set.
+    pub fn get_source(&self, option: &str) -> Option<&Path> {
+        self.sources.get(option).map(|path| path.as_path())
+    }
+}
+
+// A file and everything it extends, merged, with paths relative from the file
+#[derive(Default)]
+struct Layer {
+    fields: Map<String, Value>,
+    files: Vec<PathBuf>,
+    sources: BTreeMap<String, PathBuf>,
+}
+
+pub struct TsConfigLoader;
+
+impl TsConfigLoader {
+    /// Load a tsconfig file and every file that it extends, either by relative
+    /// path or package specifier, and merge them with TypeScript's rules: compiler
+    /// options are merged individually, `files`, `include`, and `exclude` are
+    /// replaced, and `references` are not inherited. When extending multiple files,
+    /// later files take precedence over earlier ones. Files may be JSONC.
+    pub fn load<P: AsRef<Path>>(path: P) -> miette::Result<ResolvedTsConfig> {
+        let path = normalize_path(path.as_ref());
+
+        debug!(tsconfig = ?path, "Loading tsconfig and resolving extends");
+
+        let layer = Self::load_layer(&path, &mut vec![])?;
+
+        let config: PartialTsConfig =
+            serde_json::from_value(Value::Object(layer.fields)).map_err(|error| {
+                TsConfigError::ParseFailed {
+                    path: path.clone(),
+                    error,
+                }
+            })?;
+
+        Ok(ResolvedTsConfig {
+            config,
+            files: layer.files,
+            sources: layer.sources,
+        })
+    }
+
+    /// Resolve an `extends` specifier, either a relative or absolute path,
+    /// or a package within `node_modules`, from the directory of the file
+    /// that extends it.
+    pub fn resolve_extends(specifier: &str, from_dir: &Path) -> Option<PathBuf> {
+        let is_path = specifier.starts_with("./")
+            || specifier.starts_with("../")
+            || Path::new(specifier).is_absolute();
+
+        if is_path {
+            let path = normalize_path(&from_dir.join(specifier));
+
+            return if path.is_file() {
+                Some(path)
+            } else {
+                with_json_extension(&path).filter(|path| path.is_file())
+            };
+        }
+
+        for dir in from_dir.ancestors() {
+            let path = dir.join("node_modules").join(specifier);
+
+            if path.is_file() {
+                return Some(path);
+            }
+
+            if let Some(path) = with_json_extension(&path).filter(|path| path.is_file()) {
+                return Some(path);
+            }
+
+            if !path.is_dir() {
+                continue;
+            }
+
+            // Packages may point to their tsconfig with a custom field
+            let tsconfig_field = fs::read_file(path.join("package.json"))
+                .ok()
+                .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+                .and_then(|package| package.get("tsconfig")?.as_str().map(PathBuf::from));
+
+            if let Some(file) = tsconfig_field {
+                return Some(normalize_path(&path.join(file)));
+            }
+
+            if path.join("tsconfig.json").is_file() {
+                return Some(path.join("tsconfig.json"));
+            }
+        }
+
+        None
+    }
+
+    fn load_layer(path: &Path, stack: &mut Vec<PathBuf>) -> miette::Result<Layer> {
+        if stack.iter().any(|parent| parent == path) {
+            return Err(TsConfigError::CircularExtends {
+                path: path.to_path_buf(),
+            })?;
+        }
+
+        if !path.is_file() {
+            return Err(TsConfigError::MissingFile {
+                path: path.to_path_buf(),
+            })?;
+        }
+
+        let content = fs::read_file(path)?;
+
+        let Value::Object(mut fields) = serde_json::from_str::<Value>(&strip_jsonc(&content))
+            .map_err(|error| TsConfigError::ParseFailed {
+                path: path.to_path_buf(),
+                error,
+            })?
+        else {
+            return Err(TsConfigError::ParseFailed {
+                path: path.to_path_buf(),
+                error: serde::de::Error::custom("expected an object"),
+            })?;
+        };
+
+        let dir = path.parent().unwrap();
+        let mut layer = Layer {
+            files: vec![path.to_path_buf()],
+            ..Layer::default()
+        };
+
+        stack.push(path.to_path_buf());
+
+        for specifier in Self::take_extends(path, &mut fields)? {
+            let Some(base_path) = Self::resolve_extends(&specifier, dir) else {
+                return Err(TsConfigError::ExtendsNotFound {
+                    specifier,
+                    path: path.to_path_buf(),
+                })?;
+            };
+
+            let mut base = Self::load_layer(&base_path, stack)?;
+
+            // References are never inherited
+            base.fields.remove("references");
+            base.sources.remove("references");
+
+            rebase_fields(&mut base.fields, base_path.parent().unwrap(), dir);
+
+            layer.files.extend(std::mem::take(&mut base.files));
+
+            merge_layer(&mut layer, base);
+        }
+
+        stack.pop();
+
+        // The file itself takes precedence over everything it extends
+        let own = Layer {
+            sources: fields
+                .iter()
+                .flat_map(|(key, value)| match (key.as_str(), value) {
+                    ("compilerOptions", Value::Object(options)) => options
+                        .keys()
+                        .map(|option| format!("compilerOptions.{option}"))
+                        .collect::<Vec<_>>(),
+                    _ => vec![key.to_owned()],
+                })
+                .map(|key| (key, path.to_path_buf()))
+                .collect(),
+            fields,
+            files: vec![],
+        };
+
+        merge_layer(&mut layer, own);
+
+        Ok(layer)
+    }
+
+    fn take_extends(path: &Path, fields: &mut Map<String, Value>) -> miette::Result<Vec<String>> {
+        let specifiers = match fields.remove("extends") {
+            None => vec![],
+            Some(Value::String(specifier)) => vec![specifier],
+            Some(Value::Array(list)) => list
+                .into_iter()
+                .map(|item| match item {
+                    Value::String(specifier) => Ok(specifier),
+                    _ => Err(TsConfigError::InvalidExtends {
+                        path: path.to_path_buf(),
+                    }),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Some(_) => {
+                return Err(TsConfigError::InvalidExtends {
+                    path: path.to_path_buf(),
+                })?;
+            }
+        };
+
+        Ok(specifiers)
+    }
+}
+
+fn merge_layer(layer: &mut Layer, next: Layer) {
+    for (key, value) in next.fields {
+        match (layer.fields.get_mut(&key), value) {
+            (Some(Value::Object(options)), Value::Object(next_options))
+                if key == "compilerOptions" =>
+            {
+                options.extend(next_options);
+            }
+            (_, value) => {
+                layer.fields.insert(key, value);
+            }
+        }
+    }
+
+    layer.sources.extend(next.sources);
+}
+
+// Make paths in an extended file relative from the file that extends it
+fn rebase_fields(fields: &mut Map<String, Value>, from_dir: &Path, to_dir: &Path) {
+    let prefix = diff_paths(from_dir, to_dir);
+
+    let rebase = |value: &mut Value| {
+        if let Value::String(path) = value {
+            // Absolute paths and templates like `${configDir}` are left as-is
+            if !path.starts_with('/') && !path.starts_with("${") {
+                *path = join_relative(&prefix, path);
+            }
+        }
+    };
+
+    let rebase_list = |value: &mut Value| {
+        if let Value::Array(list) = value {
+            list.iter_mut().for_each(rebase);
+        }
+    };
+
+    for field in PATH_LIST_FIELDS {
+        if let Some(value) = fields.get_mut(field) {
+            rebase_list(value);
+        }
+    }
+
+    let Some(Value::Object(options)) = fields.get_mut("compilerOptions") else {
+        return;
+    };
+
+    for option in PATH_OPTIONS {
+        if let Some(value) = options.get_mut(option) {
+            rebase(value);
+        }
+    }
+
+    for option in PATH_LIST_OPTIONS {
+        if let Some(value) = options.get_mut(option) {
+            rebase_list(value);
+        }
+    }
+
+    // Paths are relative from `baseUrl` when defined, which was rebased above,
+    // otherwise they are relative from the file that defines them
+    if !options.contains_key("baseUrl") {
+        if let Some(Value::Object(paths)) = options.get_mut("paths") {
+            paths.values_mut().for_each(rebase_list);
+        }
+    }
+}
+
+// Relative path from `to_dir` to `from_dir`, using forward slashes
+fn diff_paths(from_dir: &Path, to_dir: &Path) -> String {
+    let from_components = from_dir.components().collect::<Vec<_>>();
+    let to_components = to_dir.components().collect::<Vec<_>>();
+    let common = from_components
+        .iter()
+        .zip(&to_components)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut parts = vec![];
+
+    for _ in common..to_components.len() {
+        parts.push("..".to_owned());
+    }
+
+    for component in &from_components[common..] {
+        parts.push(component.as_os_str().to_string_lossy().into_owned());
+    }
+
+    parts.join("/")
+}
+
+fn join_relative(prefix: &str, path: &str) -> String {
+    let mut parts: Vec<&str> = vec![];
+
+    for part in prefix.split('/').chain(path.split('/')) {
+        match part {
+            "" | "." => {}
+            ".." if parts.last().is_some_and(|last| *last != "..") => {
+                parts.pop();
+            }
+            _ => parts.push(part),
+        }
+    }
+
+    if parts.is_empty() {
+        ".".into()
+    } else if parts[0] == ".." {
+        parts.join("/")
+    } else {
+        format!("./{}", parts.join("/"))
+    }
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+fn with_json_extension(path: &Path) -> Option<PathBuf> {
+    if path.extension().is_some_and(|ext| ext == "json") {
+        return None;
+    }
+
+    let mut path = path.as_os_str().to_owned();
+    path.push(".json");
+
+    Some(PathBuf::from(path))
+}
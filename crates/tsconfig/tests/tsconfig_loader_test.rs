@@ -0,0 +1,256 @@
+use espresso_tsconfig::*;
+use relative_path::RelativePathBuf;
+use starbase_sandbox::create_empty_sandbox;
+
+mod jsonc {
+    use super::*;
+
+    #[test]
+    fn strips_comments_and_trailing_commas() {
+        let content = strip_jsonc(
+            r#"{
+    // Line comment
+    "include": ["src", /* inline */ "tests",],
+    /* Block
+       comment */
+    "compilerOptions": {
+        "strict": true,
+    },
+}"#,
+        );
+
+        let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "include": ["src", "tests"],
+                "compilerOptions": { "strict": true },
+            })
+        );
+        assert_eq!(content.lines().count(), 9);
+    }
+
+    #[test]
+    fn ignores_comments_and_commas_in_strings() {
+        let content = strip_jsonc(r#"{ "paths": { "//*": ["./src/,*/\"*"] } }"#);
+
+        assert_eq!(content, r#"{ "paths": { "//*": ["./src/,*/\"*"] } }"#);
+    }
+}
+
+mod tsconfig_loader {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "No tsconfig found")]
+    fn errors_for_missing_file() {
+        let sandbox = create_empty_sandbox();
+
+        TsConfigLoader::load(sandbox.path().join("tsconfig.json")).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Unable to resolve")]
+    fn errors_for_unknown_extends() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("tsconfig.json", r#"{ "extends": "./missing.json" }"#);
+
+        TsConfigLoader::load(sandbox.path().join("tsconfig.json")).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "Circular")]
+    fn errors_for_circular_extends() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.json", r#"{ "extends": "./b.json" }"#);
+        sandbox.create_file("b.json", r#"{ "extends": "./a" }"#);
+
+        TsConfigLoader::load(sandbox.path().join("a.json")).unwrap();
+    }
+
+    #[test]
+    fn merges_compiler_options_from_extends() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "tsconfig.base.json",
+            r#"{
+    // Shared options
+    "compilerOptions": {
+        "strict": true,
+        "allowJs": true,
+    },
+    "include": ["src/**/*"],
+    "references": [{ "path": "./other" }],
+}"#,
+        );
+        sandbox.create_file(
+            "tsconfig.json",
+            r#"{
+    "extends": "./tsconfig.base",
+    "compilerOptions": {
+        "allowJs": false
+    }
+}"#,
+        );
+
+        let resolved = TsConfigLoader::load(sandbox.path().join("tsconfig.json")).unwrap();
+        let options = resolved.config.compiler_options.unwrap();
+
+        assert_eq!(options.strict, Some(true));
+        assert_eq!(options.allow_js, Some(false));
+        assert_eq!(
+            resolved.config.include,
+            Some(vec![RelativePathBuf::from("./src/**/*")])
+        );
+        assert_eq!(resolved.config.references, None);
+        assert_eq!(
+            resolved.files,
+            vec![
+                sandbox.path().join("tsconfig.json"),
+                sandbox.path().join("tsconfig.base.json"),
+            ]
+        );
+    }
+
+    #[test]
+    fn later_extends_take_precedence() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file("a.json", r#"{ "compilerOptions": { "strict": true } }"#);
+        sandbox.create_file("b.json", r#"{ "compilerOptions": { "strict": false } }"#);
+        sandbox.create_file(
+            "tsconfig.json",
+            r#"{ "extends": ["./a.json", "./b.json"] }"#,
+        );
+
+        let resolved = TsConfigLoader::load(sandbox.path().join("tsconfig.json")).unwrap();
+
+        assert_eq!(
+            resolved.config.compiler_options.as_ref().unwrap().strict,
+            Some(false)
+        );
+        assert_eq!(
+            resolved.get_source("compilerOptions.strict"),
+            Some(sandbox.path().join("b.json").as_path())
+        );
+    }
+
+    #[test]
+    fn reports_which_file_set_each_option() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "configs/base.json",
+            r#"{ "compilerOptions": { "strict": true, "noEmit": true }, "exclude": ["dist"] }"#,
+        );
+        sandbox.create_file(
+            "tsconfig.json",
+            r#"{ "extends": "./configs/base.json", "compilerOptions": { "noEmit": false } }"#,
+        );
+
+        let resolved = TsConfigLoader::load(sandbox.path().join("tsconfig.json")).unwrap();
+
+        assert_eq!(
+            resolved.sources.into_iter().collect::<Vec<_>>(),
+            vec![
+                (
+                    String::from("compilerOptions.noEmit"),
+                    sandbox.path().join("tsconfig.json")
+                ),
+                (
+                    String::from("compilerOptions.strict"),
+                    sandbox.path().join("configs/base.json")
+                ),
+                (
+                    String::from("exclude"),
+                    sandbox.path().join("configs/base.json")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rebases_paths_from_extended_files() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "tsconfig.options.json",
+            r#"{
+    "compilerOptions": {
+        "outDir": "./build",
+        "typeRoots": ["./types"],
+        "paths": {
+            "@/*": ["./shared/*"]
+        }
+    },
+    "exclude": ["**/*.test.ts"]
+}"#,
+        );
+        sandbox.create_file(
+            "packages/a/tsconfig.json",
+            r#"{ "extends": "../../tsconfig.options.json" }"#,
+        );
+
+        let resolved =
+            TsConfigLoader::load(sandbox.path().join("packages/a/tsconfig.json")).unwrap();
+        let options = resolved.config.compiler_options.unwrap();
+
+        assert_eq!(options.out_dir, Some(RelativePathBuf::from("../../build")));
+        assert_eq!(
+            options.type_roots,
+            Some(vec![RelativePathBuf::from("../../types")])
+        );
+        assert_eq!(
+            options.paths.unwrap().get("@/*"),
+            Some(&vec![RelativePathBuf::from("../../shared/*")])
+        );
+        assert_eq!(
+            resolved.config.exclude,
+            Some(vec![RelativePathBuf::from("../../**/*.test.ts")])
+        );
+    }
+
+    #[test]
+    fn resolves_package_specifiers() {
+        let sandbox = create_empty_sandbox();
+        sandbox.create_file(
+            "node_modules/@tsconfig/strictest/tsconfig.json",
+            r#"{ "compilerOptions": { "strict": true } }"#,
+        );
+        sandbox.create_file(
+            "node_modules/shared-config/package.json",
+            r#"{ "name": "shared-config", "tsconfig": "./configs/base.json" }"#,
+        );
+        sandbox.create_file(
+            "node_modules/shared-config/configs/base.json",
+            r#"{ "compilerOptions": { "noEmit": true } }"#,
+        );
+        sandbox.create_file(
+            "node_modules/shared-config/react.json",
+            r#"{ "compilerOptions": { "jsx": "react-jsx" } }"#,
+        );
+        sandbox.create_file(
+            "packages/a/tsconfig.json",
+            r#"{ "extends": ["@tsconfig/strictest", "shared-config", "shared-config/react"] }"#,
+        );
+
+        let resolved =
+            TsConfigLoader::load(sandbox.path().join("packages/a/tsconfig.json")).unwrap();
+        let options = resolved.config.compiler_options.unwrap();
+
+        assert_eq!(options.strict, Some(true));
+        assert_eq!(options.no_emit, Some(true));
+        assert_eq!(options.jsx, Some(Jsx::ReactJsx));
+        assert_eq!(
+            resolved.files,
+            vec![
+                sandbox.path().join("packages/a/tsconfig.json"),
+                sandbox
+                    .path()
+                    .join("node_modules/@tsconfig/strictest/tsconfig.json"),
+                sandbox
+                    .path()
+                    .join("node_modules/shared-config/configs/base.json"),
+                sandbox.path().join("node_modules/shared-config/react.json"),
+            ]
+        );
+    }
+}
@@ -3,8 +3,9 @@ use espresso_common::PackageName;
 use espresso_package::Package;
 use petgraph::algo::toposort;
 use petgraph::stable_graph::{NodeIndex, StableDiGraph};
+use petgraph::Direction;
 use starbase_styles::color;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use tracing::{debug, trace};
 
 // This is a simple DAG that represents the dependency graph of local
@@ -57,6 +58,69 @@ impl<'ws> PackageGraph<'ws> {
         }
     }
 
+    /// Return all packages that the provided package depends on, either
+    /// directly or transitively, sorted topologically.
+    pub fn get_dependencies(&self, name: &PackageName) -> miette::Result<Vec<&PackageName>> {
+        self.traverse(name, Direction::Outgoing)
+    }
+
+    /// Return only the packages that the provided package depends on directly.
+    pub fn get_direct_dependencies(&self, name: &PackageName) -> Vec<&PackageName> {
+        let Some(index) = self
+            .graph
+            .node_indices()
+            .find(|i| *self.graph.node_weight(*i).unwrap() == name)
+        else {
+            return vec![];
+        };
+
+        let mut names = self
+            .graph
+            .neighbors_directed(index, Direction::Outgoing)
+            .map(|i| *self.graph.node_weight(i).unwrap())
+            .collect::<Vec<_>>();
+
+        names.sort();
+        names
+    }
+
+    /// Return all packages that depend on the provided package, either
+    /// directly or transitively, sorted topologically.
+    pub fn get_dependents(&self, name: &PackageName) -> miette::Result<Vec<&PackageName>> {
+        self.traverse(name, Direction::Incoming)
+    }
+
+    fn traverse(
+        &self,
+        name: &PackageName,
+        direction: Direction,
+    ) -> miette::Result<Vec<&PackageName>> {
+        let Some(start) = self
+            .graph
+            .node_indices()
+            .find(|i| *self.graph.node_weight(*i).unwrap() == name)
+        else {
+            return Ok(vec![]);
+        };
+
+        let mut found = HashSet::new();
+        let mut queue = vec![start];
+
+        while let Some(index) = queue.pop() {
+            for neighbor in self.graph.neighbors_directed(index, direction) {
+                if found.insert(*self.graph.node_weight(neighbor).unwrap()) {
+                    queue.push(neighbor);
+                }
+            }
+        }
+
+        Ok(self
+            .toposort()?
+            .into_iter()
+            .filter(|n| found.contains(n))
+            .collect())
+    }
+
     fn add_packages(&mut self) {
         for (name, package) in self.packages {
             self.add_package(name, package);
@@ -78,14 +142,11 @@ impl<'ws> PackageGraph<'ws> {
 
         self.indices.insert(name, index);
 
-        // Loop through dependencies and find packages in the current workspace
+        // Loop through dependencies and find packages in the current workspace.
+        // Peer and optional dependencies are treated like normal edges.
         let mut edges = vec![];
 
-        let mut dependencies = BTreeMap::new();
-        dependencies.extend(&package.manifest.dependencies);
-        dependencies.extend(&package.manifest.dev_dependencies);
-
-        for dep_name in dependencies.keys() {
+        for dep_name in package.manifest.get_all_dependencies().into_keys() {
             if let Some(dep_package) = self.packages.get(dep_name) {
                 edges.push(self.add_package(dep_name, dep_package));
 
@@ -84,7 +84,15 @@ impl Workspace {
             debug!(workspace_root = ?self.root, "Loading package(s)");
 
             let mut add_package = |root: &Path| -> miette::Result<()> {
-                let package = Package::new(root)?;
+                let mut package = Package::new(root)?;
+
+                // Inherit build settings from the workspace
+                if let Manifest::Workspace(manifest) = &self.manifest {
+                    if package.manifest.build.typescript_version.is_none() {
+                        package.manifest.build.typescript_version =
+                            manifest.build.typescript_version.clone();
+                    }
+                }
 
                 debug!(
                     package = package.name(),
@@ -166,10 +174,22 @@ impl Workspace {
             return Err(WorkspaceError::NoPackagesSelected)?;
         }
 
+        let graph = PackageGraph::new(packages);
+
+        // Only the selected packages and what they depend on will be used,
+        // so unrelated packages shouldn't fail the selection
+        let mut consumer_names = selected_names.clone();
+
+        for name in &selected_names {
+            consumer_names.extend(graph.get_dependencies(name)?);
+        }
+
+        self.check_peer_dependencies(&consumer_names)?;
+
         // Sort the filtered packages topologically
         let mut results = vec![];
 
-        for name in PackageGraph::new(packages).toposort()? {
+        for name in graph.toposort()? {
             if selected_names.contains(name) {
                 results.push(packages.get(name).unwrap());
             }
@@ -189,6 +209,77 @@ impl Workspace {
         Ok(results)
     }
 
+    /// Verify that every peer dependency of a workspace package is provided by
+    /// each of the consuming packages, either directly or through the workspace.
+    /// Development dependencies are not published, so they never provide a peer.
+    /// Until dependencies are resolved from the registry, only peers that are
+    /// also workspace packages can be version checked.
+    pub fn check_peer_dependencies(
+        &self,
+        consumer_names: &HashSet<&PackageName>,
+    ) -> miette::Result<()> {
+        let packages = self.load_packages()?;
+        let shared_dependencies = match &self.manifest {
+            Manifest::Workspace(manifest) => Some(&manifest.dependencies),
+            Manifest::Package(_) => None,
+        };
+
+        debug!("Checking peer dependencies of workspace packages");
+
+        for (consumer_name, consumer) in packages {
+            if !consumer_names.contains(consumer_name) {
+                continue;
+            }
+
+            let mut provided = BTreeMap::new();
+            provided.extend(&consumer.manifest.optional_dependencies);
+            provided.extend(&consumer.manifest.peer_dependencies);
+            provided.extend(&consumer.manifest.dependencies);
+
+            for dep_name in provided.keys() {
+                let Some(dep_package) = packages.get(*dep_name) else {
+                    continue;
+                };
+
+                for (peer_name, peer_req) in &dep_package.manifest.peer_dependencies {
+                    trace!(
+                        consumer = consumer_name.as_str(),
+                        package = dep_name.as_str(),
+                        peer = peer_name.as_str(),
+                        "Checking peer dependency",
+                    );
+
+                    if !provided.contains_key(peer_name)
+                        && !shared_dependencies.is_some_and(|deps| deps.contains_key(peer_name))
+                    {
+                        return Err(WorkspaceError::MissingPeerDependency {
+                            consumer: consumer_name.to_owned(),
+                            package: (*dep_name).to_owned(),
+                            peer: peer_name.to_owned(),
+                            req: peer_req.to_owned(),
+                        })?;
+                    }
+
+                    if let Some(version) = packages
+                        .get(peer_name)
+                        .and_then(|peer| peer.manifest.package.version.as_ref())
+                    {
+                        if !peer_req.matches(version) {
+                            return Err(WorkspaceError::IncompatiblePeerDependency {
+                                package: (*dep_name).to_owned(),
+                                peer: peer_name.to_owned(),
+                                req: peer_req.to_owned(),
+                                version: version.to_owned(),
+                            })?;
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     fn find_upwards(
         current_dir: &Path,
         home_dir: &Path,
@@ -1,4 +1,4 @@
-use espresso_common::PackageName;
+use espresso_common::{PackageName, RelatedError, Version, VersionReq};
 use espresso_lockfile::LOCKFILE_NAME;
 use espresso_manifest::MANIFEST_NAME;
 use miette::Diagnostic;
@@ -7,6 +7,36 @@ use thiserror::Error;
 
 #[derive(Debug, Diagnostic, Error)]
 pub enum WorkspaceError {
+    #[diagnostic(code(workspace::peer_dependency::incompatible))]
+    #[error(
+        "The package {} requires peer dependency {} ({}), but the workspace provides version {}.",
+        .package.to_string().style(Style::Id),
+        .peer.to_string().style(Style::Id),
+        .req.to_string().style(Style::Symbol),
+        .version.to_string().style(Style::Symbol),
+    )]
+    IncompatiblePeerDependency {
+        package: PackageName,
+        peer: PackageName,
+        req: VersionReq,
+        version: Version,
+    },
+
+    #[diagnostic(code(workspace::peer_dependency::missing))]
+    #[error(
+        "The package {} requires peer dependency {} ({}), but it's not provided by consumer {}.",
+        .package.to_string().style(Style::Id),
+        .peer.to_string().style(Style::Id),
+        .req.to_string().style(Style::Symbol),
+        .consumer.to_string().style(Style::Id),
+    )]
+    MissingPeerDependency {
+        consumer: PackageName,
+        package: PackageName,
+        peer: PackageName,
+        req: VersionReq,
+    },
+
     #[diagnostic(code(workspace::package_graph::none_selected))]
     #[error(
         "No packages have been selected. Pass {} to select all packages in the workspace, {} for each package by name, or {} to filter by name.",
@@ -24,6 +54,13 @@ pub enum WorkspaceError {
     )]
     NoRootDetected,
 
+    #[diagnostic(code(workspace::packages_failed))]
+    #[error("{} packages failed.", .errors.len())]
+    PackagesFailed {
+        #[related]
+        errors: Vec<RelatedError>,
+    },
+
     #[diagnostic(code(workspace::package_graph::cycle_detected))]
     #[error(
         "Unable to continue, detected a dependency cycle for packages in the local workspace. The package {} was involved in the cycle.",
@@ -1,3 +1,4 @@
+use espresso_common::PackageName;
 use espresso_workspace::{PackageGraph, Workspace};
 use starbase_sandbox::create_sandbox;
 
@@ -33,4 +34,80 @@ mod package_graph {
             vec!["graph/dd", "graph/aa", "graph/ee", "graph/ff", "graph/cc", "graph/bb"]
         );
     }
+
+    #[test]
+    fn returns_dependents_topologically() {
+        let sandbox = create_sandbox("graph");
+        let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+        let graph = PackageGraph::new(workspace.load_packages().unwrap());
+        let names = graph
+            .get_dependents(&PackageName::parse("graph/ee").unwrap())
+            .unwrap()
+            .iter()
+            .map(|n| n.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["graph/ff", "graph/cc", "graph/bb"]);
+    }
+
+    #[test]
+    fn returns_dependencies_topologically() {
+        let sandbox = create_sandbox("graph");
+        let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+        let graph = PackageGraph::new(workspace.load_packages().unwrap());
+        let names = graph
+            .get_dependencies(&PackageName::parse("graph/cc").unwrap())
+            .unwrap()
+            .iter()
+            .map(|n| n.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["graph/dd", "graph/ee", "graph/ff"]);
+    }
+
+    #[test]
+    fn returns_direct_dependencies() {
+        let sandbox = create_sandbox("graph");
+        let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+        let graph = PackageGraph::new(workspace.load_packages().unwrap());
+        let names = graph
+            .get_direct_dependencies(&PackageName::parse("graph/cc").unwrap())
+            .iter()
+            .map(|n| n.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["graph/ff"]);
+    }
+
+    #[test]
+    fn returns_no_dependents_for_leaf() {
+        let sandbox = create_sandbox("graph");
+        let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+        let graph = PackageGraph::new(workspace.load_packages().unwrap());
+
+        assert!(graph
+            .get_dependents(&PackageName::parse("graph/bb").unwrap())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn sorts_peer_dependencies_like_normal_dependencies() {
+        let sandbox = create_sandbox("peer-deps");
+        let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+        let graph = PackageGraph::new(workspace.load_packages().unwrap());
+        let names = graph
+            .toposort()
+            .unwrap()
+            .iter()
+            .map(|n| n.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(names, vec!["peer/react", "peer/ui", "peer/app"]);
+    }
 }
@@ -1,4 +1,4 @@
-use espresso_common::PackageName;
+use espresso_common::{PackageName, VersionReq};
 use espresso_lockfile::LOCKFILE_NAME;
 use espresso_manifest::MANIFEST_NAME;
 use espresso_workspace::{SelectQuery, Workspace};
@@ -125,6 +125,52 @@ mod workspace {
             assert!(packages.contains_key(&PackageName::parse("mono/baz").unwrap()));
         }
 
+        #[test]
+        fn inherits_build_settings() {
+            let sandbox = create_sandbox("monorepo");
+            sandbox.create_file(
+                "esp.toml",
+                r#"
+[workspace]
+packages = ["packages/*"]
+
+[build]
+typescript-version = "~5.3"
+"#,
+            );
+            sandbox.create_file(
+                "packages/bar/esp.toml",
+                r#"
+[package]
+name = "mono/bar"
+
+[build]
+typescript-version = "^5.4"
+"#,
+            );
+
+            let workspace = Workspace::load_from(sandbox.path()).unwrap();
+            let packages = workspace.load_packages().unwrap();
+            let get_version = |name: &str| {
+                packages
+                    .get(&PackageName::parse(name).unwrap())
+                    .unwrap()
+                    .manifest
+                    .build
+                    .typescript_version
+                    .clone()
+            };
+
+            assert_eq!(
+                get_version("mono/foo"),
+                Some(VersionReq::parse("~5.3").unwrap())
+            );
+            assert_eq!(
+                get_version("mono/bar"),
+                Some(VersionReq::parse("^5.4").unwrap())
+            );
+        }
+
         #[test]
         fn selects_all() {
             let sandbox = create_sandbox("monorepo");
@@ -221,4 +267,87 @@ mod workspace {
                 .unwrap();
         }
     }
+
+    mod peer_dependencies {
+        use super::*;
+
+        fn select_all(workspace: &Workspace) {
+            workspace
+                .select_packages(SelectQuery {
+                    all: true,
+                    ..SelectQuery::default()
+                })
+                .unwrap();
+        }
+
+        #[test]
+        fn passes_when_provided() {
+            let sandbox = create_sandbox("peer-deps");
+            let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+            select_all(&workspace);
+        }
+
+        #[test]
+        #[should_panic(expected = "it's not provided by consumer peer/app")]
+        fn errors_when_not_provided() {
+            let sandbox = create_sandbox("peer-deps");
+            sandbox.create_file(
+                format!("app/{}", MANIFEST_NAME).as_str(),
+                "[package]\nname = \"peer/app\"\n\n[dependencies]\n\"peer/ui\" = \"*\"",
+            );
+
+            let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+            select_all(&workspace);
+        }
+
+        #[test]
+        #[should_panic(expected = "it's not provided by consumer peer/app")]
+        fn errors_when_only_a_dev_dependency() {
+            let sandbox = create_sandbox("peer-deps");
+            sandbox.create_file(
+                format!("app/{}", MANIFEST_NAME).as_str(),
+                "[package]\nname = \"peer/app\"\n\n[dependencies]\n\"peer/ui\" = \"*\"\n\n[dev-dependencies]\n\"peer/react\" = \"*\"",
+            );
+
+            let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+            select_all(&workspace);
+        }
+
+        #[test]
+        #[should_panic(expected = "workspace provides version 17.0.0")]
+        fn errors_when_incompatible_version() {
+            let sandbox = create_sandbox("peer-deps");
+            sandbox.create_file(
+                format!("react/{}", MANIFEST_NAME).as_str(),
+                "[package]\nname = \"peer/react\"\nversion = \"17.0.0\"",
+            );
+
+            let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+            select_all(&workspace);
+        }
+
+        #[test]
+        fn ignores_packages_that_are_not_selected() {
+            let sandbox = create_sandbox("peer-deps");
+            sandbox.create_file(
+                format!("app/{}", MANIFEST_NAME).as_str(),
+                "[package]\nname = \"peer/app\"\n\n[dependencies]\n\"peer/ui\" = \"*\"",
+            );
+
+            let workspace = Workspace::load_from(sandbox.path()).unwrap();
+
+            let packages = workspace
+                .select_packages(SelectQuery {
+                    names: Some(&vec![PackageName::parse("peer/ui").unwrap()]),
+                    ..SelectQuery::default()
+                })
+                .unwrap();
+
+            assert_eq!(packages.len(), 1);
+        }
+    }
 }